@@ -48,3 +48,18 @@ fn write(bencher: &mut Bencher) {
         assert_eq!(ok!(statement.next()), State::Done);
     })
 }
+
+#[bench]
+fn write_with_prepare(bencher: &mut Bencher) {
+    let connection = create();
+    let query = "INSERT INTO data (a, b, c, d) VALUES (?, ?, ?, ?)";
+
+    bencher.iter(|| {
+        let mut statement = ok!(connection.prepare(query));
+        ok!(statement.bind((1, 42)));
+        ok!(statement.bind((2, 42.0)));
+        ok!(statement.bind((3, 42.0)));
+        ok!(statement.bind((4, 42.0)));
+        assert_eq!(ok!(statement.next()), State::Done);
+    })
+}
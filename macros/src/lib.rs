@@ -0,0 +1,86 @@
+//! Procedural macros for the [sqlite][1] package.
+//!
+//! [1]: https://crates.io/crates/sqlite
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{Data, DeriveInput, Error, Expr, ExprLit, Fields, Lit, Meta};
+
+/// Derive `FromRow` for a struct with named fields.
+///
+/// Each field is read from the column with the same name unless overridden
+/// via `#[column = "name"]`.
+#[proc_macro_derive(FromRow, attributes(column))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match implement(input) {
+        Ok(output) => output.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn implement(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    name,
+                    "FromRow can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "FromRow can only be derived for structs",
+            ))
+        }
+    };
+    let mut reads = vec![];
+    for field in fields {
+        let identifier = field.ident.as_ref().unwrap();
+        let kind = &field.ty;
+        let column = column(field)?.unwrap_or_else(|| identifier.to_string());
+        reads.push(quote! {
+            #identifier: row.try_read::<#kind, _>(#column)?
+        });
+    }
+    let (implementation, kind, clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #implementation ::sqlite::FromRow for #name #kind #clause {
+            fn from_row(row: &::sqlite::Row) -> ::sqlite::Result<Self> {
+                Ok(#name { #(#reads,)* })
+            }
+        }
+    })
+}
+
+fn column(field: &syn::Field) -> Result<Option<String>, Error> {
+    for attribute in &field.attrs {
+        if !attribute.path().is_ident("column") {
+            continue;
+        }
+        if let Meta::NameValue(ref meta) = attribute.meta {
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Str(ref value),
+                ..
+            }) = meta.value
+            {
+                return Ok(Some(value.value()));
+            }
+        }
+        return Err(Error::new_spanned(
+            attribute,
+            "expected an attribute of the form #[column = \"name\"]",
+        ));
+    }
+    Ok(None)
+}
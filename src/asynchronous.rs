@@ -0,0 +1,158 @@
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+
+use tokio::task::JoinHandle;
+
+use connection::Connection;
+use cursor::Row;
+use error::Result;
+use statement::Bindable;
+use transaction::Transaction;
+
+/// A database connection for asynchronous code.
+///
+/// The operations are run via `tokio::task::spawn_blocking` so that they do
+/// not block the executor, one at a time. The connection is cheap to clone,
+/// with the clones sharing the underlying connection. Prepared statements
+/// borrow the connection and are therefore only available inside `call`.
+#[derive(Clone)]
+pub struct AsyncConnection {
+    connection: Arc<Mutex<Connection>>,
+}
+
+/// An operation on an asynchronous connection.
+///
+/// The operation starts running when the task is first polled. If it panics,
+/// the panic is propagated to the caller awaiting the task.
+pub struct Task<T>(State<T>);
+
+enum State<T> {
+    Created(Option<Box<dyn FnOnce() -> Result<T> + Send>>),
+    Running(JoinHandle<Result<T>>),
+}
+
+impl AsyncConnection {
+    /// Open a read-write connection to a new or existing database.
+    pub fn open<T: AsRef<Path>>(path: T) -> Task<AsyncConnection> {
+        let path = path.as_ref().to_path_buf();
+        Task::new(move || Connection::open(path).map(AsyncConnection::from))
+    }
+
+    /// Run a function on the underlying connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{AsyncConnection, State};
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// let connection = runtime.block_on(AsyncConnection::open(":memory:"))?;
+    /// let count = runtime.block_on(connection.call(|connection| {
+    ///     let mut statement = connection.prepare("SELECT 1 UNION SELECT 2")?;
+    ///     let mut count = 0;
+    ///     while let State::Row = statement.next()? {
+    ///         count += 1;
+    ///     }
+    ///     Ok(count)
+    /// }))?;
+    /// assert_eq!(count, 2);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn call<F, T>(&self, function: F) -> Task<T>
+    where
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let connection = self.connection.clone();
+        Task::new(move || {
+            let mut connection = connection.lock().unwrap_or_else(PoisonError::into_inner);
+            function(&mut connection)
+        })
+    }
+
+    /// Execute a statement without processing the resulting rows if any.
+    #[inline]
+    pub fn execute<T: Into<String>>(&self, statement: T) -> Task<()> {
+        let statement = statement.into();
+        self.call(move |connection| connection.execute(statement))
+    }
+
+    /// Execute a statement expected to return exactly one row and map it.
+    ///
+    /// See `Connection::query_row` for details.
+    pub fn query_row<T, U, F, V>(&self, statement: T, parameters: U, map: F) -> Task<V>
+    where
+        T: Into<String>,
+        U: Bindable + Send + 'static,
+        F: FnOnce(&Row) -> Result<V> + Send + 'static,
+        V: Send + 'static,
+    {
+        let statement = statement.into();
+        self.call(move |connection| connection.query_row(statement, parameters, map))
+    }
+
+    /// Run a function within a transaction.
+    ///
+    /// The transaction is committed if the function succeeds and rolled back
+    /// otherwise.
+    pub fn transaction<F, T>(&self, function: F) -> Task<T>
+    where
+        F: FnOnce(&mut Transaction<'_>) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.call(move |connection| {
+            let mut transaction = connection.transaction()?;
+            let value = function(&mut transaction)?;
+            transaction.commit()?;
+            Ok(value)
+        })
+    }
+}
+
+impl From<Connection> for AsyncConnection {
+    #[inline]
+    fn from(connection: Connection) -> Self {
+        AsyncConnection {
+            connection: Arc::new(Mutex::new(connection)),
+        }
+    }
+}
+
+impl<T: Send + 'static> Task<T> {
+    fn new<F>(function: F) -> Self
+    where
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
+        Task(State::Created(Some(Box::new(function))))
+    }
+}
+
+impl<T: Send + 'static> Future for Task<T> {
+    type Output = Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        if let State::Created(ref mut function) = self.0 {
+            let function = function.take().expect("the task has already been started");
+            self.0 = State::Running(tokio::task::spawn_blocking(function));
+        }
+        let handle = match self.0 {
+            State::Running(ref mut handle) => handle,
+            _ => unreachable!(),
+        };
+        match Pin::new(handle).poll(context) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(error)) if error.is_panic() => {
+                std::panic::resume_unwind(error.into_panic())
+            }
+            Poll::Ready(Err(error)) => Poll::Ready(Err(::Error {
+                code: None,
+                extended_code: None,
+                message: Some(format!("failed to run a task ({})", error)),
+            })),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use ffi;
+use libc::{c_char, c_int, c_void};
+
+/// An action subject to authorization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthorizerAction<'l> {
+    /// Create an index.
+    CreateIndex { index: &'l str, table: &'l str },
+    /// Create a table.
+    CreateTable { table: &'l str },
+    /// Create a temporary index.
+    CreateTempIndex { index: &'l str, table: &'l str },
+    /// Create a temporary table.
+    CreateTempTable { table: &'l str },
+    /// Create a temporary trigger.
+    CreateTempTrigger { trigger: &'l str, table: &'l str },
+    /// Create a temporary view.
+    CreateTempView { view: &'l str },
+    /// Create a trigger.
+    CreateTrigger { trigger: &'l str, table: &'l str },
+    /// Create a view.
+    CreateView { view: &'l str },
+    /// Delete rows from a table.
+    Delete { table: &'l str },
+    /// Drop an index.
+    DropIndex { index: &'l str, table: &'l str },
+    /// Drop a table.
+    DropTable { table: &'l str },
+    /// Drop a temporary index.
+    DropTempIndex { index: &'l str, table: &'l str },
+    /// Drop a temporary table.
+    DropTempTable { table: &'l str },
+    /// Drop a temporary trigger.
+    DropTempTrigger { trigger: &'l str, table: &'l str },
+    /// Drop a temporary view.
+    DropTempView { view: &'l str },
+    /// Drop a trigger.
+    DropTrigger { trigger: &'l str, table: &'l str },
+    /// Drop a view.
+    DropView { view: &'l str },
+    /// Insert rows into a table.
+    Insert { table: &'l str },
+    /// Run a pragma.
+    Pragma {
+        name: &'l str,
+        value: Option<&'l str>,
+    },
+    /// Read a column.
+    ReadColumn { table: &'l str, column: &'l str },
+    /// Run a SELECT statement.
+    Select,
+    /// Begin, commit, or roll back a transaction.
+    Transaction { operation: &'l str },
+    /// Update a column.
+    Update { table: &'l str, column: &'l str },
+    /// Attach a database.
+    Attach { filename: &'l str },
+    /// Detach a database.
+    Detach { database: &'l str },
+    /// Alter a table.
+    AlterTable { database: &'l str, table: &'l str },
+    /// Rebuild an index.
+    Reindex { index: &'l str },
+    /// Analyze a table.
+    Analyze { table: &'l str },
+    /// Create a virtual table.
+    CreateVirtualTable { table: &'l str, module: &'l str },
+    /// Drop a virtual table.
+    DropVirtualTable { table: &'l str, module: &'l str },
+    /// Call a function.
+    Function { function: &'l str },
+    /// Begin, release, or roll back a savepoint.
+    Savepoint { operation: &'l str, name: &'l str },
+    /// Run a recursive query.
+    Recursive,
+    /// An action unknown to the crate.
+    Unknown { code: i32 },
+}
+
+/// A decision of an authorizer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Authorization {
+    /// Allow the action.
+    Ok,
+    /// Reject the statement with an error.
+    Deny,
+    /// Allow the statement but disallow the action, which, for instance,
+    /// makes reading a column return NULL.
+    Ignore,
+}
+
+impl<'l> AuthorizerAction<'l> {
+    unsafe fn new(code: c_int, first: *const c_char, second: *const c_char) -> Self {
+        let first_or_none = read(first);
+        let second_or_none = read(second);
+        let first = first_or_none.unwrap_or("");
+        let second = second_or_none.unwrap_or("");
+        match code {
+            ffi::SQLITE_CREATE_INDEX => AuthorizerAction::CreateIndex {
+                index: first,
+                table: second,
+            },
+            ffi::SQLITE_CREATE_TABLE => AuthorizerAction::CreateTable { table: first },
+            ffi::SQLITE_CREATE_TEMP_INDEX => AuthorizerAction::CreateTempIndex {
+                index: first,
+                table: second,
+            },
+            ffi::SQLITE_CREATE_TEMP_TABLE => AuthorizerAction::CreateTempTable { table: first },
+            ffi::SQLITE_CREATE_TEMP_TRIGGER => AuthorizerAction::CreateTempTrigger {
+                trigger: first,
+                table: second,
+            },
+            ffi::SQLITE_CREATE_TEMP_VIEW => AuthorizerAction::CreateTempView { view: first },
+            ffi::SQLITE_CREATE_TRIGGER => AuthorizerAction::CreateTrigger {
+                trigger: first,
+                table: second,
+            },
+            ffi::SQLITE_CREATE_VIEW => AuthorizerAction::CreateView { view: first },
+            ffi::SQLITE_DELETE => AuthorizerAction::Delete { table: first },
+            ffi::SQLITE_DROP_INDEX => AuthorizerAction::DropIndex {
+                index: first,
+                table: second,
+            },
+            ffi::SQLITE_DROP_TABLE => AuthorizerAction::DropTable { table: first },
+            ffi::SQLITE_DROP_TEMP_INDEX => AuthorizerAction::DropTempIndex {
+                index: first,
+                table: second,
+            },
+            ffi::SQLITE_DROP_TEMP_TABLE => AuthorizerAction::DropTempTable { table: first },
+            ffi::SQLITE_DROP_TEMP_TRIGGER => AuthorizerAction::DropTempTrigger {
+                trigger: first,
+                table: second,
+            },
+            ffi::SQLITE_DROP_TEMP_VIEW => AuthorizerAction::DropTempView { view: first },
+            ffi::SQLITE_DROP_TRIGGER => AuthorizerAction::DropTrigger {
+                trigger: first,
+                table: second,
+            },
+            ffi::SQLITE_DROP_VIEW => AuthorizerAction::DropView { view: first },
+            ffi::SQLITE_INSERT => AuthorizerAction::Insert { table: first },
+            ffi::SQLITE_PRAGMA => AuthorizerAction::Pragma {
+                name: first,
+                value: second_or_none,
+            },
+            ffi::SQLITE_READ => AuthorizerAction::ReadColumn {
+                table: first,
+                column: second,
+            },
+            ffi::SQLITE_SELECT => AuthorizerAction::Select,
+            ffi::SQLITE_TRANSACTION => AuthorizerAction::Transaction { operation: first },
+            ffi::SQLITE_UPDATE => AuthorizerAction::Update {
+                table: first,
+                column: second,
+            },
+            ffi::SQLITE_ATTACH => AuthorizerAction::Attach { filename: first },
+            ffi::SQLITE_DETACH => AuthorizerAction::Detach { database: first },
+            ffi::SQLITE_ALTER_TABLE => AuthorizerAction::AlterTable {
+                database: first,
+                table: second,
+            },
+            ffi::SQLITE_REINDEX => AuthorizerAction::Reindex { index: first },
+            ffi::SQLITE_ANALYZE => AuthorizerAction::Analyze { table: first },
+            ffi::SQLITE_CREATE_VTABLE => AuthorizerAction::CreateVirtualTable {
+                table: first,
+                module: second,
+            },
+            ffi::SQLITE_DROP_VTABLE => AuthorizerAction::DropVirtualTable {
+                table: first,
+                module: second,
+            },
+            ffi::SQLITE_FUNCTION => AuthorizerAction::Function { function: second },
+            ffi::SQLITE_SAVEPOINT => AuthorizerAction::Savepoint {
+                operation: first,
+                name: second,
+            },
+            ffi::SQLITE_RECURSIVE => AuthorizerAction::Recursive,
+            code => AuthorizerAction::Unknown { code },
+        }
+    }
+}
+
+pub extern "C" fn callback<F>(
+    callback: *mut c_void,
+    code: c_int,
+    first: *const c_char,
+    second: *const c_char,
+    _: *const c_char,
+    _: *const c_char,
+) -> c_int
+where
+    F: FnMut(AuthorizerAction<'_>) -> Authorization,
+{
+    unsafe {
+        let action = AuthorizerAction::new(code, first, second);
+        ::connection::guard(ffi::SQLITE_DENY, || match (*(callback as *mut F))(action) {
+            Authorization::Ok => ffi::SQLITE_OK,
+            Authorization::Deny => ffi::SQLITE_DENY,
+            Authorization::Ignore => ffi::SQLITE_IGNORE,
+        })
+    }
+}
+
+unsafe fn read<'l>(pointer: *const c_char) -> Option<&'l str> {
+    if pointer.is_null() {
+        None
+    } else {
+        c_str_to_str!(pointer).ok()
+    }
+}
@@ -0,0 +1,90 @@
+use ffi;
+use libc::c_int;
+use std::marker::PhantomData;
+
+use connection::Connection;
+use error::Result;
+
+/// An online backup.
+///
+/// The backup is finished when dropped.
+pub struct BackupHandle<'l> {
+    raw: *mut ffi::sqlite3_backup,
+    destination: *mut ffi::sqlite3,
+    phantom: PhantomData<(&'l Connection, &'l mut Connection)>,
+}
+
+/// The progress of a backup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BackupStatus {
+    /// The number of pages still to be copied.
+    pub remaining: usize,
+    /// The total number of pages in the source database.
+    pub total: usize,
+    /// The flag indicating whether the backup has been completed.
+    pub done: bool,
+}
+
+impl<'l> BackupHandle<'l> {
+    /// Copy up to a number of pages, with a negative number meaning all the
+    /// remaining pages.
+    ///
+    /// If the source or destination database is busy or locked, the call
+    /// succeeds without copying anything and can be retried later.
+    pub fn step(&mut self, page_count: i32) -> Result<BackupStatus> {
+        let done = unsafe {
+            match ffi::sqlite3_backup_step(self.raw, page_count as c_int) {
+                ffi::SQLITE_DONE => true,
+                ffi::SQLITE_OK | ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED => false,
+                code => error!(self.destination, code),
+            }
+        };
+        unsafe {
+            Ok(BackupStatus {
+                remaining: ffi::sqlite3_backup_remaining(self.raw) as usize,
+                total: ffi::sqlite3_backup_pagecount(self.raw) as usize,
+                done,
+            })
+        }
+    }
+
+    /// Finish the backup.
+    pub fn finish(mut self) -> Result<()> {
+        let raw = std::mem::replace(&mut self.raw, std::ptr::null_mut());
+        unsafe { ok!(self.destination, ffi::sqlite3_backup_finish(raw)) };
+        Ok(())
+    }
+}
+
+impl<'l> Drop for BackupHandle<'l> {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.raw.is_null() {
+            unsafe { ffi::sqlite3_backup_finish(self.raw) };
+        }
+    }
+}
+
+pub fn new<'l>(
+    source: &'l Connection,
+    destination: &'l mut Connection,
+    schema: &str,
+) -> Result<BackupHandle<'l>> {
+    let schema = str_to_cstr!(schema);
+    let raw = unsafe {
+        ffi::sqlite3_backup_init(
+            destination.as_raw(),
+            schema.as_ptr(),
+            source.as_raw(),
+            schema.as_ptr(),
+        )
+    };
+    if raw.is_null() {
+        error!(destination.as_raw(), ffi::SQLITE_ERROR);
+    }
+    Ok(BackupHandle {
+        raw,
+        destination: destination.as_raw(),
+        phantom: PhantomData,
+    })
+}
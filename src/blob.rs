@@ -0,0 +1,160 @@
+use ffi;
+use libc::c_int;
+use std::io;
+use std::marker::PhantomData;
+
+use connection::Connection;
+use error::Result;
+
+/// A handle for incremental I/O on a BLOB.
+///
+/// The handle is closed when dropped. The size of the BLOB cannot be changed
+/// via the handle.
+pub struct BlobHandle<'l> {
+    raw: *mut ffi::sqlite3_blob,
+    connection: *mut ffi::sqlite3,
+    offset: usize,
+    phantom: PhantomData<&'l Connection>,
+}
+
+impl<'l> BlobHandle<'l> {
+    /// Return the size in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        unsafe { ffi::sqlite3_blob_bytes(self.raw) as usize }
+    }
+
+    /// Check if the BLOB is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Move to the BLOB in the same column of another row.
+    ///
+    /// The position is reset to the beginning.
+    pub fn reopen(&mut self, rowid: i64) -> Result<()> {
+        unsafe {
+            ok!(
+                self.connection,
+                ffi::sqlite3_blob_reopen(self.raw, rowid as ffi::sqlite3_int64)
+            );
+        }
+        self.offset = 0;
+        Ok(())
+    }
+
+    fn read_at(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let count = buffer.len().min(self.len().saturating_sub(self.offset));
+        if count == 0 {
+            return Ok(0);
+        }
+        unsafe {
+            ok!(
+                self.connection,
+                ffi::sqlite3_blob_read(
+                    self.raw,
+                    buffer.as_mut_ptr() as *mut _,
+                    count as c_int,
+                    self.offset as c_int,
+                )
+            );
+        }
+        self.offset += count;
+        Ok(count)
+    }
+
+    fn write_at(&mut self, buffer: &[u8]) -> Result<usize> {
+        let count = buffer.len().min(self.len().saturating_sub(self.offset));
+        if count == 0 {
+            return Ok(0);
+        }
+        unsafe {
+            ok!(
+                self.connection,
+                ffi::sqlite3_blob_write(
+                    self.raw,
+                    buffer.as_ptr() as *const _,
+                    count as c_int,
+                    self.offset as c_int,
+                )
+            );
+        }
+        self.offset += count;
+        Ok(count)
+    }
+}
+
+impl<'l> Drop for BlobHandle<'l> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { ffi::sqlite3_blob_close(self.raw) };
+    }
+}
+
+impl<'l> io::Read for BlobHandle<'l> {
+    #[inline]
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.read_at(buffer).map_err(io::Error::other)
+    }
+}
+
+impl<'l> io::Write for BlobHandle<'l> {
+    #[inline]
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        self.write_at(buffer).map_err(io::Error::other)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'l> io::Seek for BlobHandle<'l> {
+    fn seek(&mut self, position: io::SeekFrom) -> io::Result<u64> {
+        let offset = match position {
+            io::SeekFrom::Start(offset) => offset as i64,
+            io::SeekFrom::Current(offset) => self.offset as i64 + offset,
+            io::SeekFrom::End(offset) => self.len() as i64 + offset,
+        };
+        if offset < 0 || offset > self.len() as i64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "failed to seek outside of the BLOB",
+            ));
+        }
+        self.offset = offset as usize;
+        Ok(offset as u64)
+    }
+}
+
+pub fn new<'l>(
+    connection: &'l Connection,
+    table: &str,
+    column: &str,
+    rowid: i64,
+    write: bool,
+) -> Result<BlobHandle<'l>> {
+    let mut raw = std::ptr::null_mut();
+    unsafe {
+        ok!(
+            connection.as_raw(),
+            ffi::sqlite3_blob_open(
+                connection.as_raw(),
+                str_to_cstr!("main").as_ptr(),
+                str_to_cstr!(table).as_ptr(),
+                str_to_cstr!(column).as_ptr(),
+                rowid as ffi::sqlite3_int64,
+                write as c_int,
+                &mut raw,
+            )
+        );
+    }
+    Ok(BlobHandle {
+        raw,
+        connection: connection.as_raw(),
+        offset: 0,
+        phantom: PhantomData,
+    })
+}
@@ -0,0 +1,115 @@
+use ffi;
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+
+use connection::Connection;
+use error::Result;
+use statement::Statement;
+
+/// A prepared statement borrowed from the cache of a connection.
+///
+/// The statement is reset, its parameters are cleared, and it is returned to
+/// the cache when dropped.
+pub struct CachedStatement<'l> {
+    connection: &'l Connection,
+    query: String,
+    statement: Option<Statement<'l>>,
+}
+
+pub struct StatementCache {
+    capacity: usize,
+    entries: VecDeque<(String, Raw)>,
+}
+
+struct Raw(*mut ffi::sqlite3_stmt);
+
+impl<'l> Deref for CachedStatement<'l> {
+    type Target = Statement<'l>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.statement.as_ref().unwrap()
+    }
+}
+
+impl<'l> DerefMut for CachedStatement<'l> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.statement.as_mut().unwrap()
+    }
+}
+
+impl<'l> Drop for CachedStatement<'l> {
+    #[allow(unused_must_use)]
+    fn drop(&mut self) {
+        if let Some(mut statement) = self.statement.take() {
+            statement.reset();
+            statement.clear_bindings();
+            let query = std::mem::take(&mut self.query);
+            let raw = ::statement::into_raw(statement);
+            ::connection::statement_cache(self.connection).insert(query, raw);
+        }
+    }
+}
+
+impl StatementCache {
+    pub fn new(capacity: usize) -> Self {
+        StatementCache {
+            capacity,
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn insert(&mut self, query: String, raw: *mut ffi::sqlite3_stmt) {
+        if self.entries.iter().any(|(other, _)| *other == query) {
+            drop(Raw(raw));
+            return;
+        }
+        self.entries.push_back((query, Raw(raw)));
+        self.evict();
+    }
+
+    fn remove(&mut self, query: &str) -> Option<*mut ffi::sqlite3_stmt> {
+        let position = self.entries.iter().position(|(other, _)| other == query)?;
+        let (_, raw) = self.entries.remove(position)?;
+        let raw = std::mem::ManuallyDrop::new(raw);
+        Some(raw.0)
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+}
+
+impl Drop for Raw {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { ffi::sqlite3_finalize(self.0) };
+    }
+}
+
+unsafe impl Send for Raw {}
+
+pub fn new<'l>(connection: &'l Connection, query: &str) -> Result<CachedStatement<'l>> {
+    let raw = ::connection::statement_cache(connection).remove(query);
+    let statement = match raw {
+        Some(raw) => ::statement::from_raw(raw, connection.as_raw()),
+        _ => connection.prepare(query)?,
+    };
+    Ok(CachedStatement {
+        connection,
+        query: query.to_string(),
+        statement: Some(statement),
+    })
+}
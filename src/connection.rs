@@ -1,43 +1,2369 @@
 use ffi;
 use libc::{c_char, c_int, c_void};
+use std::cmp::Ordering;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
-use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
+use authorizer::{Authorization, AuthorizerAction};
+use backup::BackupHandle;
+use blob::BlobHandle;
+use cache::{CachedStatement, StatementCache};
+use cursor::{CursorWithOwnership, MappedCursor, Row};
 use error::Result;
-use statement::Statement;
+#[cfg(feature = "window")]
+use function::WindowAggregate;
+use function::{Aggregate, CollationHandle, FunctionContext, FunctionFlags};
+#[cfg(feature = "snapshot")]
+use snapshot::Snapshot;
+use statement::{Bindable, ReadableWithIndex, State, Statement};
+use trace::{TraceEvent, TraceFlags};
+use transaction::Transaction;
+use value::Value;
+
+// https://www.sqlite.org/c3ref/deserialize.html
+#[cfg(feature = "serialize")]
+const SQLITE_DESERIALIZE_FREEONCLOSE: u32 = 1;
+#[cfg(feature = "serialize")]
+const SQLITE_DESERIALIZE_RESIZABLE: u32 = 2;
+
+// https://www.sqlite.org/c3ref/c_open_autoproxy.html
+const SQLITE_OPEN_NOFOLLOW: c_int = 0x01000000;
+const SQLITE_OPEN_EXRESCODE: c_int = 0x02000000;
+
+extern "C" {
+    // https://www.sqlite.org/c3ref/serialize.html
+    #[cfg(feature = "serialize")]
+    fn sqlite3_serialize(
+        db: *mut ffi::sqlite3,
+        schema: *const c_char,
+        size: *mut ffi::sqlite3_int64,
+        flags: u32,
+    ) -> *mut u8;
+
+    // https://www.sqlite.org/c3ref/deserialize.html
+    #[cfg(feature = "serialize")]
+    fn sqlite3_deserialize(
+        db: *mut ffi::sqlite3,
+        schema: *const c_char,
+        data: *mut u8,
+        size: ffi::sqlite3_int64,
+        capacity: ffi::sqlite3_int64,
+        flags: u32,
+    ) -> c_int;
+
+    // https://www.sqlite.org/c3ref/changes.html
+    #[cfg(feature = "changes64")]
+    fn sqlite3_changes64(db: *mut ffi::sqlite3) -> ffi::sqlite3_int64;
+
+    // https://www.sqlite.org/c3ref/total_changes.html
+    #[cfg(feature = "changes64")]
+    fn sqlite3_total_changes64(db: *mut ffi::sqlite3) -> ffi::sqlite3_int64;
+}
 
 /// A database connection.
 pub struct Connection {
     raw: Raw,
+    authorizer_callback: Option<Box<AuthorizerCallback>>,
     busy_callback: Option<Box<dyn FnMut(usize) -> bool + Send>>,
+    collation_callback: Option<Box<CollationCallback>>,
+    commit_callback: Option<Box<dyn FnMut() -> bool + Send>>,
+    progress_callback: Option<Box<dyn FnMut() -> bool + Send>>,
+    rollback_callback: Option<Box<dyn FnMut() + Send>>,
+    trace_callback: Option<Box<TraceCallback>>,
+    update_callback: Option<Box<UpdateCallback>>,
+    unlock_notify_callback: Option<Box<UnlockNotifyCallback>>,
+    wal_callback: Option<Box<WalCallback>>,
+    statement_cache: Mutex<StatementCache>,
     phantom: PhantomData<ffi::sqlite3>,
 }
 
 /// A thread-safe database connection.
+#[derive(Debug)]
 pub struct ConnectionWithFullMutex(Connection);
 
+/// A builder of a database connection.
+///
+/// By default, the connection is opened for reading and writing, and the
+/// database is expected to exist unless `create` is called.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite::ConnectionBuilder;
+///
+/// let connection = ConnectionBuilder::new()
+///     .path("database.sqlite3")
+///     .create()
+///     .busy_timeout(500)
+///     .foreign_keys(true)
+///     .wal_mode()
+///     .open()?;
+/// # Ok::<(), sqlite::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConnectionBuilder {
+    path: Option<PathBuf>,
+    flags: OpenFlags,
+    busy_timeout: Option<usize>,
+    foreign_keys: Option<bool>,
+    journal_mode: Option<JournalMode>,
+}
+
+/// A kind of row change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// A row has been inserted.
+    Insert,
+    /// A row has been updated.
+    Update,
+    /// A row has been deleted.
+    Delete,
+}
+
+/// The metadata of a column of a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnMetadata {
+    /// The declared type, which is empty if none has been declared.
+    pub declared_type: String,
+    /// The name of the collating sequence, which is `BINARY` by default.
+    pub collation: String,
+    /// The presence of a NOT NULL constraint.
+    pub not_null: bool,
+    /// The membership in the primary key.
+    pub primary_key: bool,
+    /// The presence of the AUTOINCREMENT keyword.
+    pub auto_increment: bool,
+}
+
+/// A [conflict resolution mode][1] of a virtual-table update.
+///
+/// [1]: https://www.sqlite.org/lang_conflict.html
+#[cfg(feature = "vtab")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictMode {
+    /// Abort the current statement and roll back the transaction.
+    Rollback,
+    /// Abort the current statement and back out its changes.
+    Abort,
+    /// Abort the current statement but keep its prior changes.
+    Fail,
+    /// Skip the row with the constraint violation.
+    Ignore,
+    /// Delete the conflicting rows before inserting or updating the row.
+    Replace,
+}
+
+/// A database attached to a connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttachedDatabase {
+    /// The sequence number, which is 0 for the main database and 1 for the
+    /// temporary one.
+    pub seq: i32,
+    /// The schema name.
+    pub name: String,
+    /// The path to the file, which is empty for in-memory and temporary
+    /// databases.
+    pub file: String,
+}
+
+/// A [status counter][1] of a connection.
+///
+/// [1]: https://www.sqlite.org/c3ref/c_dbstatus_options.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DbStatus {
+    /// The number of lookaside memory slots currently checked out.
+    LookasideUsed,
+    /// The number of bytes of heap memory used by the page cache.
+    CacheUsed,
+    /// The number of bytes of heap memory used to store the schema.
+    SchemaUsed,
+    /// The number of bytes of heap memory used by prepared statements.
+    StmtUsed,
+    /// The number of memory requests satisfied using lookaside memory.
+    LookasideHit,
+    /// The number of memory requests that could not be satisfied using
+    /// lookaside memory due to their size.
+    LookasideMissSize,
+    /// The number of memory requests that could not be satisfied using
+    /// lookaside memory because all of it was in use.
+    LookasideMissFull,
+    /// The number of page-cache hits.
+    CacheHit,
+    /// The number of page-cache misses.
+    CacheMiss,
+    /// The number of dirty pages written to the disk.
+    CacheWrite,
+    /// Whether there are unresolved deferred foreign-key constraints, which
+    /// is indicated by 1.
+    DeferredFks,
+    /// Same as `CacheUsed` but with the memory of shared caches divided
+    /// evenly among the connections sharing them.
+    CacheUsedShared,
+}
+
+/// A violation of a foreign-key constraint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignKeyViolation {
+    /// The table containing the offending row.
+    pub table: String,
+    /// The rowid of the offending row, which is `None` for tables without
+    /// rowids.
+    pub rowid: Option<i64>,
+    /// The table referred to by the violated constraint.
+    pub parent: String,
+    /// The index of the violated constraint among the constraints of the
+    /// table.
+    pub fkid: usize,
+}
+
+/// A journal mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JournalMode {
+    /// The rollback journal is deleted at the end of each transaction.
+    Delete,
+    /// The rollback journal is truncated at the end of each transaction.
+    Truncate,
+    /// The rollback journal header is zeroed at the end of each transaction.
+    Persist,
+    /// The rollback journal is kept in memory.
+    Memory,
+    /// A write-ahead log is used instead of a rollback journal.
+    Wal,
+    /// The rollback journal is disabled.
+    Off,
+}
+
+/// A step of a query plan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryPlanNode {
+    /// The identifier of the step.
+    pub id: i32,
+    /// The identifier of the parent step, which is 0 for top-level steps.
+    pub parent_id: i32,
+    /// The description of the step.
+    pub detail: String,
+}
+
+/// A prepared statement of a connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatementInfo {
+    /// The SQL text used to prepare the statement.
+    pub sql: String,
+    /// The flag indicating that the statement has been stepped but neither
+    /// run to completion nor reset.
+    pub is_busy: bool,
+}
+
+/// A [run-time limit][1] of a connection.
+///
+/// [1]: https://www.sqlite.org/limits.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    /// The maximum size of a string or blob or of a row.
+    Length,
+    /// The maximum length of an SQL statement in bytes.
+    SqlLength,
+    /// The maximum number of columns in a table, an index, a view, or a result
+    /// set.
+    Column,
+    /// The maximum depth of the parse tree of an expression.
+    ExprDepth,
+    /// The maximum number of terms in a compound SELECT statement.
+    CompoundSelect,
+    /// The maximum number of instructions in a prepared statement.
+    VdbeOp,
+    /// The maximum number of arguments of a function.
+    FunctionArg,
+    /// The maximum number of attached databases.
+    Attached,
+    /// The maximum length of the pattern of a LIKE or GLOB operator.
+    LikePatternLength,
+    /// The maximum index of a parameter in an SQL statement.
+    VariableNumber,
+    /// The maximum depth of recursion of triggers.
+    TriggerDepth,
+    /// The maximum number of auxiliary worker threads of a prepared statement.
+    WorkerThreads,
+}
+
+/// A level of synchronization with the file system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Synchronous {
+    /// Hand data over to the operating system without syncing.
+    Off,
+    /// Sync at the most critical moments.
+    Normal,
+    /// Sync to ensure that a crash cannot corrupt the database.
+    Full,
+    /// Same as `Full` but also sync the directory of a rollback journal.
+    Extra,
+}
+
+/// A mode of checkpointing a write-ahead log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WalCheckpointMode {
+    /// Checkpoint as many frames as possible without waiting for readers or
+    /// writers.
+    Passive,
+    /// Wait for writers and checkpoint all frames.
+    Full,
+    /// Same as `Full` but also wait for readers so that the log can be
+    /// restarted.
+    Restart,
+    /// Same as `Restart` but also truncate the log.
+    Truncate,
+}
+
 /// Flags for opening a database connection.
 #[derive(Clone, Copy, Debug)]
 pub struct OpenFlags(c_int);
 
 struct Raw(*mut ffi::sqlite3);
 
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+type AuthorizerCallback = dyn FnMut(AuthorizerAction<'_>) -> Authorization + Send;
+type CollationCallback = dyn FnMut(&CollationHandle<'_>, &str) + Send;
+type TraceCallback = dyn FnMut(TraceEvent<'_>) + Send;
+type UnlockNotifyCallback = Mutex<Option<Box<dyn FnOnce() + Send>>>;
+type UpdateCallback = dyn FnMut(Action, &str, &str, i64) + Send;
+type WalCallback = dyn FnMut(&str, usize) + Send;
+
 impl Connection {
+    /// Create a builder of a connection to a database.
+    ///
+    /// See `ConnectionBuilder` for details.
+    #[inline]
+    pub fn builder<T: AsRef<Path>>(path: T) -> ConnectionBuilder {
+        ConnectionBuilder::new().path(path)
+    }
+
     /// Open a read-write connection to a new or existing database.
     pub fn open<T: AsRef<Path>>(path: T) -> Result<Connection> {
         Connection::open_with_flags(path, OpenFlags::new().set_create().set_read_write())
     }
 
-    /// Open a connection with specific flags.
-    pub fn open_with_flags<T: AsRef<Path>>(path: T, flags: OpenFlags) -> Result<Connection> {
+    /// Open a connection with specific flags.
+    #[inline]
+    pub fn open_with_flags<T: AsRef<Path>>(path: T, flags: OpenFlags) -> Result<Connection> {
+        Connection::open_with_flags_and_vfs(path.as_ref(), flags, None)
+    }
+
+    /// Open a connection with specific flags via a registered [VFS][1].
+    ///
+    /// [1]: https://www.sqlite.org/vfs.html
+    #[inline]
+    pub fn open_with_vfs<T: AsRef<Path>>(
+        path: T,
+        flags: OpenFlags,
+        vfs: &str,
+    ) -> Result<Connection> {
+        Connection::open_with_flags_and_vfs(path.as_ref(), flags, Some(vfs))
+    }
+
+    /// Open a thread-safe read-write connection to a new or existing database.
+    pub fn open_with_full_mutex<T: AsRef<Path>>(path: T) -> Result<ConnectionWithFullMutex> {
+        Connection::open_with_flags(
+            path,
+            OpenFlags::new()
+                .set_create()
+                .set_read_write()
+                .set_full_mutex(),
+        )
+        .map(ConnectionWithFullMutex)
+    }
+
+    /// Open a read-write connection to an in-memory database.
+    ///
+    /// If a name is given, the database is opened in shared-cache mode, and
+    /// all connections opened with the same name within the process share the
+    /// same database. Otherwise, the database is private to the connection.
+    pub fn open_memory(name: Option<&str>) -> Result<Connection> {
+        match name {
+            Some(name) => Connection::open_with_flags(
+                format!("file:{}?mode=memory&cache=shared", encode(name)),
+                OpenFlags::new().set_create().set_read_write().set_uri(),
+            ),
+            _ => Connection::open(":memory:"),
+        }
+    }
+
+    /// Open a read-write connection to a new or existing database in
+    /// [shared-cache mode][1].
+    ///
+    /// The path is interpreted as a [URI filename][2], so that, for instance,
+    /// `file:name?mode=memory` refers to an in-memory database shared by all
+    /// connections opened with the same name within the process.
+    ///
+    /// Connections sharing a cache use table-level locking, and an operation
+    /// conflicting with a lock held by another connection fails immediately
+    /// with a locking error (code 6) instead of invoking the busy handler.
+    /// Each connection is still subject to the usual threading restrictions,
+    /// and the mode is discouraged by SQLite outside of such special cases.
+    ///
+    /// [1]: https://www.sqlite.org/sharedcache.html
+    /// [2]: https://www.sqlite.org/uri.html
+    pub fn open_shared_cache<T: AsRef<Path>>(path: T) -> Result<Connection> {
+        Connection::open_with_flags(
+            path,
+            OpenFlags::new()
+                .set_create()
+                .set_read_write()
+                .set_shared_cache()
+                .set_uri(),
+        )
+    }
+
+    /// Return the number of bytes of heap memory currently used by SQLite
+    /// across all connections.
+    #[inline]
+    pub fn memory_used() -> i64 {
+        unsafe { ffi::sqlite3_memory_used() as i64 }
+    }
+
+    /// Return the highest number of bytes of heap memory used by SQLite
+    /// across all connections.
+    ///
+    /// If `reset` is `true`, the highest value is reset to the current one
+    /// after being read.
+    #[inline]
+    pub fn memory_highwater(reset: bool) -> i64 {
+        unsafe { ffi::sqlite3_memory_highwater(reset as c_int) as i64 }
+    }
+
+    /// Set the soft limit on the heap memory used by SQLite across all
+    /// connections and return the previous one.
+    ///
+    /// Zero disables the limit, and negative values leave it unchanged.
+    #[inline]
+    pub fn soft_heap_limit(limit: i64) -> i64 {
+        unsafe { ffi::sqlite3_soft_heap_limit64(limit as ffi::sqlite3_int64) as i64 }
+    }
+
+    /// Return the version of the SQLite library in use, such as `3.40.1`.
+    #[inline]
+    pub fn sqlite_version() -> &'static str {
+        unsafe { c_str_to_str!(ffi::sqlite3_libversion()).unwrap_or_default() }
+    }
+
+    /// Return the version of the SQLite library in use as a number, such as
+    /// `3040001` for `3.40.1`.
+    #[inline]
+    pub fn sqlite_version_number() -> u32 {
+        unsafe { ffi::sqlite3_libversion_number() as u32 }
+    }
+
+    /// Return the identifier of the check-in the SQLite library in use has
+    /// been built from.
+    #[inline]
+    pub fn sqlite_source_id() -> &'static str {
+        unsafe { c_str_to_str!(ffi::sqlite3_sourceid()).unwrap_or_default() }
+    }
+
+    /// Execute a statement without processing the resulting rows if any.
+    #[inline]
+    pub fn execute<T: AsRef<str>>(&self, statement: T) -> Result<()> {
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_exec(
+                    self.raw.0,
+                    str_to_cstr!(statement.as_ref()).as_ptr(),
+                    None,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Execute statements one by one and return the number of rows inserted,
+    /// updated, or deleted by each statement.
+    ///
+    /// The resulting rows if any are not processed, and the counts include the
+    /// changes made by triggers. In case of a failure, the error message
+    /// includes the index of the failed statement, starting from 0, and the
+    /// subsequent statements are not executed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// let counts = connection.execute_batch(
+    ///     "
+    ///     CREATE TABLE users (name TEXT);
+    ///     INSERT INTO users VALUES ('Alice'), ('Bob');
+    ///     DELETE FROM users WHERE name = 'Bob';
+    ///     ",
+    /// )?;
+    /// assert_eq!(counts, vec![0, 2, 1]);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn execute_batch<T: AsRef<str>>(&self, statements: T) -> Result<Vec<usize>> {
+        let statements = str_to_cstr!(statements.as_ref());
+        let mut tail = statements.as_ptr();
+        let mut counts = vec![];
+        unsafe {
+            while *tail != 0 {
+                let mut raw = std::ptr::null_mut();
+                let code = ffi::sqlite3_prepare_v2(self.raw.0, tail, -1, &mut raw, &mut tail);
+                if code != ffi::SQLITE_OK {
+                    return Err(batch_error(self.raw.0, code, counts.len()));
+                }
+                if raw.is_null() {
+                    continue;
+                }
+                let total_count = self.total_change_count();
+                let code = loop {
+                    match ffi::sqlite3_step(raw) {
+                        ffi::SQLITE_ROW => {}
+                        code => break code,
+                    }
+                };
+                if code != ffi::SQLITE_DONE {
+                    let error = batch_error(self.raw.0, code, counts.len());
+                    ffi::sqlite3_finalize(raw);
+                    return Err(error);
+                }
+                ffi::sqlite3_finalize(raw);
+                counts.push(self.total_change_count() - total_count);
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Execute a statement with parameters and return the number of rows
+    /// inserted, updated, or deleted.
+    ///
+    /// The resulting rows if any are not processed. The count is the one given
+    /// by `change_count` once the statement is done.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::Value;
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT, age INTEGER)")?;
+    /// let query = "INSERT INTO users VALUES (?, ?)";
+    /// let values = [Value::String("Alice".into()), Value::Integer(42)];
+    /// assert_eq!(connection.execute_with_params(query, values)?, 1);
+    /// let query = "UPDATE users SET age = age + 1 WHERE name = :name";
+    /// assert_eq!(connection.execute_with_params(query, [(":name", "Alice")])?, 1);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn execute_with_params<T, U>(&self, statement: T, parameters: U) -> Result<usize>
+    where
+        T: AsRef<str>,
+        U: Bindable,
+    {
+        let mut statement = self.prepare(statement)?;
+        statement.bind(parameters)?;
+        while let State::Row = statement.next()? {}
+        Ok(self.change_count())
+    }
+
+    /// Insert rows into a table and return the number of rows inserted.
+    ///
+    /// The rows are inserted via a single prepared statement within a
+    /// savepoint, so either all rows are inserted or none is. Each row should
+    /// have as many values as there are columns. The table and column names are
+    /// quoted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::Value;
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT, age INTEGER)")?;
+    /// let rows = vec![
+    ///     vec![Value::String("Alice".into()), Value::Integer(42)],
+    ///     vec![Value::String("Bob".into()), Value::Integer(69)],
+    /// ];
+    /// assert_eq!(connection.copy_from("users", &["name", "age"], rows)?, 2);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn copy_from<T>(&self, table: &str, columns: &[&str], rows: T) -> Result<usize>
+    where
+        T: IntoIterator<Item = Vec<Value>>,
+    {
+        use transaction::quote;
+
+        if columns.is_empty() {
+            raise!("at least one column should be given");
+        }
+        let statement = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote(table),
+            columns
+                .iter()
+                .map(|column| quote(column))
+                .collect::<Vec<_>>()
+                .join(", "),
+            vec!["?"; columns.len()].join(", "),
+        );
+        let mut statement = self.prepare(statement)?;
+        self.execute("SAVEPOINT copy_from")?;
+        let result = (|| {
+            let mut count = 0;
+            for row in rows {
+                if row.len() != columns.len() {
+                    raise!("the row should have {} values", columns.len());
+                }
+                statement.reset()?;
+                for (index, value) in row.into_iter().enumerate() {
+                    statement.bind((index + 1, value))?;
+                }
+                while let State::Row = statement.next()? {}
+                count += 1;
+            }
+            Ok(count)
+        })();
+        match result {
+            Ok(count) => {
+                self.execute("RELEASE copy_from")?;
+                Ok(count)
+            }
+            Err(error) => {
+                let _ = statement.reset();
+                let _ = self.execute("ROLLBACK TO copy_from; RELEASE copy_from");
+                Err(error)
+            }
+        }
+    }
+
+    /// Insert serializable rows into a table and return the number of rows
+    /// inserted.
+    ///
+    /// Each row should serialize to a struct or a map whose fields are column
+    /// names and whose values are primitives, with `Option::None` mapped to
+    /// NULL. The columns are taken from the first row, and the other rows
+    /// should have the same ones. See `copy_from` for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT, age INTEGER)")?;
+    /// let rows = vec![
+    ///     BTreeMap::from([("name", "Alice"), ("age", "42")]),
+    ///     BTreeMap::from([("name", "Bob"), ("age", "69")]),
+    /// ];
+    /// assert_eq!(connection.insert_batch("users", &rows)?, 2);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn insert_batch<T: serde::Serialize>(&self, table: &str, rows: &[T]) -> Result<usize> {
+        use std::collections::HashMap;
+
+        let rows = rows
+            .iter()
+            .map(::serializer::to_row)
+            .collect::<Result<Vec<_>>>()?;
+        let columns = match rows.first() {
+            Some(row) => row.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>(),
+            _ => return Ok(0),
+        };
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                if row.len() != columns.len() {
+                    raise!("the row should have {} columns", columns.len());
+                }
+                let mut row = row.into_iter().collect::<HashMap<_, _>>();
+                columns
+                    .iter()
+                    .map(|column| match row.remove(column) {
+                        Some(value) => Ok(value),
+                        _ => raise!("the row should have a column named {}", column),
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let columns = columns.iter().map(String::as_str).collect::<Vec<_>>();
+        self.copy_from(table, &columns, rows)
+    }
+
+    /// Execute a statement and process the resulting rows as plain text.
+    ///
+    /// The callback is triggered for each row. If the callback returns `false`,
+    /// no more rows will be processed. For large queries and non-string data
+    /// types, prepared statement are highly preferable; see `prepare`.
+    #[inline]
+    pub fn iterate<T: AsRef<str>, F>(&self, statement: T, callback: F) -> Result<()>
+    where
+        F: FnMut(&[(&str, Option<&str>)]) -> bool,
+    {
+        unsafe {
+            let callback = Box::new(callback);
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_exec(
+                    self.raw.0,
+                    str_to_cstr!(statement.as_ref()).as_ptr(),
+                    Some(process_callback::<F>),
+                    &*callback as *const F as *mut F as *mut _,
+                    std::ptr::null_mut(),
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Create a prepared statement.
+    #[inline]
+    pub fn prepare<T: AsRef<str>>(&self, statement: T) -> Result<Statement<'_>> {
+        ::statement::new(self.raw.0, statement)
+    }
+
+    /// Create a prepared statement, bind values to its parameters, and iterate
+    /// over the resulting rows.
+    ///
+    /// The cursor owns the statement, so it can be returned from a function
+    /// that only borrows the connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{Connection, CursorWithOwnership};
+    ///
+    /// fn adults(connection: &Connection) -> sqlite::Result<CursorWithOwnership<'_>> {
+    ///     connection.query("SELECT name FROM users WHERE age >= ?", [18])
+    /// }
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT, age INTEGER)")?;
+    /// connection.execute("INSERT INTO users VALUES ('Alice', 42), ('Bob', 7)")?;
+    ///
+    /// let names = adults(&connection)?
+    ///     .map(|row| row.map(|row| row.read::<String, _>("name")))
+    ///     .collect::<sqlite::Result<Vec<_>>>()?;
+    /// assert_eq!(names, vec!["Alice"]);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn query<T, U>(&self, statement: T, parameters: U) -> Result<CursorWithOwnership<'_>>
+    where
+        T: AsRef<str>,
+        U: Bindable,
+    {
+        self.prepare(statement)?.into_iter().bind(parameters)
+    }
+
+    /// Create a prepared statement, bind values to its parameters, and map
+    /// the resulting rows lazily.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT, age INTEGER)")?;
+    /// connection.execute("INSERT INTO users VALUES ('Alice', 42), ('Bob', 69)")?;
+    ///
+    /// let query = "SELECT name, age FROM users WHERE age > ? ORDER BY age";
+    /// let users = connection
+    ///     .query_map(query, [40], |row| {
+    ///         Ok((row.try_get::<String, _>("name")?, row.try_get::<i64, _>("age")?))
+    ///     })?
+    ///     .collect::<sqlite::Result<Vec<_>>>()?;
+    /// assert_eq!(users, vec![("Alice".into(), 42), ("Bob".into(), 69)]);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn query_map<T, U, F, V>(
+        &self,
+        statement: T,
+        parameters: U,
+        map: F,
+    ) -> Result<MappedCursor<CursorWithOwnership<'_>, F>>
+    where
+        T: AsRef<str>,
+        U: Bindable,
+        F: FnMut(Row) -> Result<V>,
+    {
+        Ok(self.query(statement, parameters)?.map_rows(map))
+    }
+
+    /// Execute a statement expected to return exactly one row and process the
+    /// row.
+    ///
+    /// An error is returned if the statement returns no rows or more than one
+    /// row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT, age INTEGER)")?;
+    /// connection.execute("INSERT INTO users VALUES ('Alice', 42)")?;
+    ///
+    /// let query = "SELECT COUNT(*) FROM users WHERE age > ?";
+    /// let count = connection.query_row(query, [40], |row| row.try_read::<i64, _>(0))?;
+    /// assert_eq!(count, 1);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn query_row<T, U, F, V>(&self, statement: T, parameters: U, map: F) -> Result<V>
+    where
+        T: AsRef<str>,
+        U: Bindable,
+        F: FnOnce(&Row) -> Result<V>,
+    {
+        let mut statement = self.prepare(statement)?;
+        let row = statement.iter().bind(parameters)?.exactly_one()?;
+        map(&row)
+    }
+
+    /// Create a prepared statement or take it from the cache.
+    ///
+    /// The statement is returned to the cache when dropped. The cache keeps
+    /// the most recently used statements; see `set_statement_cache_capacity`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT)")?;
+    /// for name in ["Alice", "Bob"] {
+    ///     let mut statement = connection.prepare_cached("INSERT INTO users VALUES (?)")?;
+    ///     statement.bind((1, name))?;
+    ///     statement.next()?;
+    /// }
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn prepare_cached<T: AsRef<str>>(&self, statement: T) -> Result<CachedStatement<'_>> {
+        ::cache::new(self, statement.as_ref())
+    }
+
+    /// Set the number of statements kept by `prepare_cached`, which is 64 by
+    /// default.
+    ///
+    /// A capacity of 0 disables caching.
+    pub fn set_statement_cache_capacity(&mut self, capacity: usize) {
+        statement_cache(self).set_capacity(capacity);
+    }
+
+    /// Return the prepared statements that have not been finalized yet.
+    ///
+    /// Note that the statements kept by `prepare_cached` are included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// let mut statement = connection.prepare("SELECT 1")?;
+    /// statement.next()?;
+    /// let statements = connection.prepared_statements();
+    /// assert_eq!(statements.len(), 1);
+    /// assert_eq!(statements[0].sql, "SELECT 1");
+    /// assert!(statements[0].is_busy);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn prepared_statements(&self) -> Vec<StatementInfo> {
+        let mut statements = vec![];
+        unsafe {
+            let mut raw = ffi::sqlite3_next_stmt(self.raw.0, std::ptr::null_mut());
+            while !raw.is_null() {
+                let sql = ffi::sqlite3_sql(raw);
+                statements.push(StatementInfo {
+                    sql: if sql.is_null() {
+                        String::new()
+                    } else {
+                        c_str_to_string!(sql)
+                    },
+                    is_busy: ffi::sqlite3_stmt_busy(raw) != 0,
+                });
+                raw = ffi::sqlite3_next_stmt(self.raw.0, raw);
+            }
+        }
+        statements
+    }
+
+    /// Return the query plan of a statement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (id INTEGER, name TEXT)")?;
+    /// let plan = connection.explain_query_plan("SELECT * FROM users WHERE id = 42")?;
+    /// assert!(plan[0].detail.contains("users"));
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn explain_query_plan<T: AsRef<str>>(&self, statement: T) -> Result<Vec<QueryPlanNode>> {
+        let mut statement = self.prepare(format!("EXPLAIN QUERY PLAN {}", statement.as_ref()))?;
+        let mut nodes = vec![];
+        while let State::Row = statement.next()? {
+            nodes.push(QueryPlanNode {
+                id: statement.read(0)?,
+                parent_id: statement.read(1)?,
+                detail: statement.read(3)?,
+            });
+        }
+        Ok(nodes)
+    }
+
+    /// Execute a statement and return the first column of the first row.
+    ///
+    /// The function returns `None` if the statement returns no rows or if the
+    /// value is NULL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (id INTEGER)")?;
+    /// let query = "SELECT MAX(id) FROM users";
+    /// assert_eq!(connection.query_scalar::<i64, _>(query)?, None);
+    /// connection.execute("INSERT INTO users VALUES (42)")?;
+    /// assert_eq!(connection.query_scalar::<i64, _>(query)?, Some(42));
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn query_scalar<T, U>(&self, statement: U) -> Result<Option<T>>
+    where
+        T: ReadableWithIndex,
+        U: AsRef<str>,
+    {
+        let mut statement = self.prepare(statement)?;
+        match statement.next()? {
+            State::Row => statement.read::<Option<T>, _>(0),
+            State::Done => Ok(None),
+        }
+    }
+
+    /// Write the dirty pages of all open transactions to the database files
+    /// without committing the transactions.
+    ///
+    /// The function fails if a dirty page cannot be written, for instance,
+    /// because the database is locked by another connection.
+    #[inline]
+    pub fn cache_flush(&self) -> Result<()> {
+        unsafe { ok!(self.raw.0, ffi::sqlite3_db_cacheflush(self.raw.0)) };
+        Ok(())
+    }
+
+    /// Release as much heap memory as possible held by the connection and
+    /// return the number of bytes released.
+    #[inline]
+    pub fn release_memory(&self) -> i32 {
+        unsafe { ffi::sqlite3_db_release_memory(self.raw.0) as i32 }
+    }
+
+    /// Enable or disable the reporting of [extended result codes][1] in place
+    /// of primary ones, which is disabled by default.
+    ///
+    /// When enabled, `Error::code` contains, for instance, 787 for a violated
+    /// foreign-key constraint instead of 19 for any violated constraint.
+    ///
+    /// [1]: https://www.sqlite.org/rescode.html#extrc
+    #[inline]
+    pub fn set_extended_result_codes(&mut self, enabled: bool) -> Result<()> {
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_extended_result_codes(self.raw.0, enabled as c_int)
+            );
+        }
+        Ok(())
+    }
+
+    /// Return the current and the highest value of a status counter.
+    ///
+    /// If `reset` is `true`, the highest value is reset to the current one
+    /// after being read. Some counters, such as `CacheHit`, have no highest
+    /// value, in which case `reset` resets the current value instead, and the
+    /// highest value is 0.
+    pub fn db_status(&self, status: DbStatus, reset: bool) -> Result<(i32, i32)> {
+        let mut current: c_int = 0;
+        let mut highest: c_int = 0;
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_db_status(
+                    self.raw.0,
+                    status.as_raw(),
+                    &mut current,
+                    &mut highest,
+                    reset as c_int,
+                )
+            );
+        }
+        Ok((current as i32, highest as i32))
+    }
+
+    /// Interrupt the operations running on the connection.
+    ///
+    /// The function is safe to call from a thread different from the one
+    /// running the operations, such as via `ConnectionWithFullMutex`. The
+    /// interrupted operations fail with an interruption error (code 9).
+    /// Operations started after all running ones have finished are not
+    /// affected.
+    #[inline]
+    pub fn interrupt(&self) {
+        unsafe { ffi::sqlite3_interrupt(self.raw.0) };
+    }
+
+    /// Return a run-time limit.
+    #[inline]
+    pub fn get_limit(&self, limit: Limit) -> i32 {
+        unsafe { ffi::sqlite3_limit(self.raw.0, limit.as_raw(), -1) as i32 }
+    }
+
+    /// Set a run-time limit and return the previous one.
+    ///
+    /// Negative values leave the limit unchanged, and values exceeding the
+    /// compile-time upper bound are silently truncated to that bound.
+    #[inline]
+    pub fn set_limit(&self, limit: Limit, value: i32) -> i32 {
+        unsafe { ffi::sqlite3_limit(self.raw.0, limit.as_raw(), value as c_int) as i32 }
+    }
+
+    /// Check if the connection is in the autocommit mode, which is the case
+    /// unless a transaction is in progress.
+    #[inline]
+    pub fn is_autocommit(&self) -> bool {
+        unsafe { ffi::sqlite3_get_autocommit(self.raw.0) != 0 }
+    }
+
+    /// Begin a transaction.
+    ///
+    /// The transaction is rolled back when dropped unless it has been
+    /// committed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT)")?;
+    ///
+    /// let transaction = connection.transaction()?;
+    /// transaction.execute("INSERT INTO users VALUES ('Alice')")?;
+    /// transaction.commit()?;
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn transaction(&mut self) -> Result<Transaction<'_>> {
+        ::transaction::new(self)
+    }
+
+    /// Return the number of rows inserted, updated, or deleted by the most
+    /// recent INSERT, UPDATE, or DELETE statement.
+    #[inline]
+    pub fn change_count(&self) -> usize {
+        unsafe { ffi::sqlite3_changes(self.raw.0) as usize }
+    }
+
+    /// Return the total number of rows inserted, updated, and deleted by all
+    /// INSERT, UPDATE, and DELETE statements since the connection was opened.
+    #[inline]
+    pub fn total_change_count(&self) -> usize {
+        unsafe { ffi::sqlite3_total_changes(self.raw.0) as usize }
+    }
+
+    /// Return the number of rows changed by the most recent statement as a
+    /// 64-bit integer, which does not overflow unlike `change_count`.
+    ///
+    /// The function requires SQLite 3.37 or newer.
+    #[cfg(feature = "changes64")]
+    #[inline]
+    pub fn change_count64(&self) -> u64 {
+        unsafe { sqlite3_changes64(self.raw.0) as u64 }
+    }
+
+    /// Return the total number of rows changed since the connection was
+    /// opened as a 64-bit integer, which does not overflow unlike
+    /// `total_change_count`.
+    ///
+    /// The function requires SQLite 3.37 or newer.
+    #[cfg(feature = "changes64")]
+    #[inline]
+    pub fn total_change_count64(&self) -> u64 {
+        unsafe { sqlite3_total_changes64(self.raw.0) as u64 }
+    }
+
+    /// Return the rowid of the most recent successful INSERT into a rowid
+    /// table.
+    ///
+    /// If no such INSERT has taken place since the connection was opened, the
+    /// function returns 0.
+    #[inline]
+    pub fn last_insert_rowid(&self) -> i64 {
+        unsafe { ffi::sqlite3_last_insert_rowid(self.raw.0) as i64 }
+    }
+
+    /// Return the path to the file of a database.
+    ///
+    /// The function returns `None` for in-memory and temporary databases as
+    /// well as for unknown schemas.
+    pub fn db_filename(&self, schema: &str) -> Option<PathBuf> {
+        let schema = std::ffi::CString::new(schema).ok()?;
+        unsafe {
+            let pointer = ffi::sqlite3_db_filename(self.raw.0, schema.as_ptr());
+            if pointer.is_null() {
+                return None;
+            }
+            match c_str_to_str!(pointer) {
+                Ok(path) if !path.is_empty() => Some(PathBuf::from(path)),
+                _ => None,
+            }
+        }
+    }
+
+    /// Check if the main database is in memory.
+    #[inline]
+    pub fn is_in_memory(&self) -> bool {
+        self.db_filename("main").is_none()
+    }
+
+    /// Check if a database is read-only, given its schema name such as `main`.
+    pub fn is_readonly(&self, schema: &str) -> Result<bool> {
+        match unsafe { ffi::sqlite3_db_readonly(self.raw.0, str_to_cstr!(schema).as_ptr()) } {
+            -1 => raise!("the schema is unknown ({})", schema),
+            code => Ok(code == 1),
+        }
+    }
+
+    /// Attach a new or existing database under a schema name.
+    ///
+    /// The path and the name are passed to SQLite as parameters, so no quoting
+    /// is needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.attach(":memory:", "other")?;
+    /// connection.execute("CREATE TABLE other.users (name TEXT)")?;
+    /// connection.detach("other")?;
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn attach<T: AsRef<Path>>(&self, path: T, name: &str) -> Result<()> {
+        let path = match path.as_ref().to_str() {
+            Some(path) => path,
+            _ => raise!("failed to process a path"),
+        };
+        let mut statement = self.prepare("ATTACH DATABASE ? AS ?")?;
+        statement.bind(&[path, name][..])?;
+        while let State::Row = statement.next()? {}
+        Ok(())
+    }
+
+    /// Detach a database attached via `attach`.
+    pub fn detach(&self, name: &str) -> Result<()> {
+        let mut statement = self.prepare("DETACH DATABASE ?")?;
+        statement.bind((1, name))?;
+        while let State::Row = statement.next()? {}
+        Ok(())
+    }
+
+    /// List the databases of the connection, including the main and
+    /// temporary ones.
+    pub fn attached_databases(&self) -> Result<Vec<AttachedDatabase>> {
+        let mut statement = self.prepare("PRAGMA database_list")?;
+        let mut databases = vec![];
+        while let State::Row = statement.next()? {
+            databases.push(AttachedDatabase {
+                seq: statement.read::<i64, _>(0)? as i32,
+                name: statement.read(1)?,
+                file: statement.read(2)?,
+            });
+        }
+        Ok(databases)
+    }
+
+    /// Enable or disable the loading of extensions via `load_extension`.
+    ///
+    /// Only the C interface is affected; the `load_extension` SQL function
+    /// remains disabled.
+    #[cfg(feature = "loadable-extension")]
+    #[inline]
+    pub fn enable_load_extension(&self, enabled: bool) -> Result<()> {
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_db_config(
+                    self.raw.0,
+                    ffi::SQLITE_DBCONFIG_ENABLE_LOAD_EXTENSION,
+                    enabled as c_int,
+                    std::ptr::null_mut::<c_int>(),
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Load an extension from a shared library.
+    ///
+    /// If no entry point is given, SQLite derives one from the name of the
+    /// file. Loading has to be enabled via `enable_load_extension` first.
+    #[cfg(feature = "loadable-extension")]
+    pub fn load_extension<T: AsRef<Path>>(&self, path: T, entry_point: Option<&str>) -> Result<()> {
+        let path = path_to_cstr!(path.as_ref());
+        let entry_point = match entry_point {
+            Some(entry_point) => Some(str_to_cstr!(entry_point)),
+            _ => None,
+        };
+        let mut message = std::ptr::null_mut();
+        unsafe {
+            let code = ffi::sqlite3_load_extension(
+                self.raw.0,
+                path.as_ptr(),
+                entry_point
+                    .as_ref()
+                    .map_or(std::ptr::null(), |entry_point| entry_point.as_ptr()),
+                &mut message,
+            );
+            if code != ffi::SQLITE_OK {
+                let message = if message.is_null() {
+                    None
+                } else {
+                    let string = c_str_to_string!(message);
+                    ffi::sqlite3_free(message as *mut _);
+                    Some(string)
+                };
+                return Err(::Error {
+                    code: Some(code as isize),
+                    extended_code: None,
+                    message,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if a table exists.
+    pub fn table_exists(&self, name: &str) -> Result<bool> {
+        let query =
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ? COLLATE NOCASE";
+        let count = self.query_row(query, [name], |row| row.try_read::<i64, _>(0))?;
+        Ok(count > 0)
+    }
+
+    /// Return the names of the tables.
+    ///
+    /// Internal tables, such as `sqlite_sequence`, are excluded.
+    #[inline]
+    pub fn tables(&self) -> Result<Vec<String>> {
+        self.schema_names("table", None)
+    }
+
+    /// Return the names of the views.
+    #[inline]
+    pub fn views(&self) -> Result<Vec<String>> {
+        self.schema_names("view", None)
+    }
+
+    /// Return the names of the indexes, optionally only those of a table.
+    ///
+    /// Indexes created automatically for UNIQUE and PRIMARY KEY constraints
+    /// are excluded.
+    #[inline]
+    pub fn indexes(&self, table: Option<&str>) -> Result<Vec<String>> {
+        self.schema_names("index", table)
+    }
+
+    /// Return the metadata of a column of a table.
+    ///
+    /// The database is searched in the usual order when `None` is given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")?;
+    /// let metadata = connection.column_metadata(None, "users", "name")?;
+    /// assert_eq!(metadata.declared_type, "TEXT");
+    /// assert!(metadata.not_null);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn column_metadata(
+        &self,
+        database: Option<&str>,
+        table: &str,
+        column: &str,
+    ) -> Result<ColumnMetadata> {
+        let database = match database {
+            Some(database) => Some(str_to_cstr!(database)),
+            _ => None,
+        };
+        let table = str_to_cstr!(table);
+        let column = str_to_cstr!(column);
+        let mut declared_type = std::ptr::null();
+        let mut collation = std::ptr::null();
+        let mut not_null = 0;
+        let mut primary_key = 0;
+        let mut auto_increment = 0;
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_table_column_metadata(
+                    self.raw.0,
+                    database
+                        .as_ref()
+                        .map_or(std::ptr::null(), |database| database.as_ptr()),
+                    table.as_ptr(),
+                    column.as_ptr(),
+                    &mut declared_type,
+                    &mut collation,
+                    &mut not_null,
+                    &mut primary_key,
+                    &mut auto_increment,
+                )
+            );
+            Ok(ColumnMetadata {
+                declared_type: if declared_type.is_null() {
+                    String::new()
+                } else {
+                    c_str_to_string!(declared_type)
+                },
+                collation: if collation.is_null() {
+                    String::from("BINARY")
+                } else {
+                    c_str_to_string!(collation)
+                },
+                not_null: not_null != 0,
+                primary_key: primary_key != 0,
+                auto_increment: auto_increment != 0,
+            })
+        }
+    }
+
+    /// Set a pragma.
+    ///
+    /// The name is inserted into the statement as is, which makes it possible
+    /// to prefix it with a schema, as in `main.cache_size`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.pragma("cache_size", -4096i64)?;
+    /// assert_eq!(connection.pragma_value::<i64>("cache_size")?, Some(-4096));
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn pragma<T: Into<Value>>(&self, name: &str, value: T) -> Result<()> {
+        let statement = format!("PRAGMA {} = {}", name, literal(&value.into())?);
+        let mut statement = self.prepare(statement)?;
+        while let State::Row = statement.next()? {}
+        Ok(())
+    }
+
+    /// Read a pragma.
+    ///
+    /// The function returns the first column of the first row, or `None` if
+    /// the pragma returns no rows or NULL.
+    #[inline]
+    pub fn pragma_value<T: ReadableWithIndex>(&self, name: &str) -> Result<Option<T>> {
+        self.query_scalar(format!("PRAGMA {}", name))
+    }
+
+    /// Return the user version, which is an integer stored in the database
+    /// header and left to the application, typically for tracking schema
+    /// migrations.
+    #[inline]
+    pub fn user_version(&self) -> Result<i32> {
+        self.pragma_value::<i64>("user_version")
+            .map(|version| version.unwrap_or(0) as i32)
+    }
+
+    /// Set the user version.
+    #[inline]
+    pub fn set_user_version(&self, version: i32) -> Result<()> {
+        self.pragma("user_version", version as i64)
+    }
+
+    /// Return the application identifier, which is an integer stored in the
+    /// database header for identifying the file format.
+    #[inline]
+    pub fn application_id(&self) -> Result<i32> {
+        self.pragma_value::<i64>("application_id")
+            .map(|id| id.unwrap_or(0) as i32)
+    }
+
+    /// Set the application identifier.
+    #[inline]
+    pub fn set_application_id(&self, id: i32) -> Result<()> {
+        self.pragma("application_id", id as i64)
+    }
+
+    /// Check if foreign-key constraints are enforced.
+    #[inline]
+    pub fn foreign_keys(&self) -> Result<bool> {
+        self.pragma_value::<i64>("foreign_keys")
+            .map(|value| value == Some(1))
+    }
+
+    /// Enable or disable the enforcement of foreign-key constraints, which is
+    /// disabled by default.
+    ///
+    /// The setting cannot be changed within a transaction.
+    #[inline]
+    pub fn enable_foreign_keys(&self, enabled: bool) -> Result<()> {
+        self.pragma("foreign_keys", enabled as i64)
+    }
+
+    /// Find rows violating foreign-key constraints, optionally only in a
+    /// table.
+    pub fn foreign_key_check(&self, table: Option<&str>) -> Result<Vec<ForeignKeyViolation>> {
+        let statement = match table {
+            Some(table) => format!("PRAGMA foreign_key_check({})", ::transaction::quote(table)),
+            _ => "PRAGMA foreign_key_check".to_string(),
+        };
+        let mut statement = self.prepare(statement)?;
+        let mut violations = vec![];
+        while let State::Row = statement.next()? {
+            violations.push(ForeignKeyViolation {
+                table: statement.read(0)?,
+                rowid: statement.read(1)?,
+                parent: statement.read(2)?,
+                fkid: statement.read::<i64, _>(3)? as usize,
+            });
+        }
+        Ok(violations)
+    }
+
+    /// Check the integrity of the database.
+    ///
+    /// The function returns a description of each problem found, with no
+    /// problems meaning that the database is healthy.
+    #[inline]
+    pub fn integrity_check(&self) -> Result<Vec<String>> {
+        self.check("integrity_check")
+    }
+
+    /// Check the integrity of the database faster but less thoroughly than
+    /// `integrity_check`.
+    #[inline]
+    pub fn quick_check(&self) -> Result<Vec<String>> {
+        self.check("quick_check")
+    }
+
+    /// Return the journal mode.
+    pub fn journal_mode(&self) -> Result<JournalMode> {
+        match self.pragma_value::<String>("journal_mode")? {
+            Some(mode) => JournalMode::parse(&mode),
+            _ => raise!("failed to read the journal mode"),
+        }
+    }
+
+    /// Set the journal mode and return the resulting one.
+    ///
+    /// The resulting mode might differ from the requested one; for instance,
+    /// in-memory databases support only `Memory` and `Off`.
+    pub fn set_journal_mode(&self, mode: JournalMode) -> Result<JournalMode> {
+        match self.pragma_value::<String>(&format!("journal_mode = {}", mode.as_str()))? {
+            Some(mode) => JournalMode::parse(&mode),
+            _ => raise!("failed to set the journal mode"),
+        }
+    }
+
+    /// Return the synchronization level.
+    pub fn synchronous(&self) -> Result<Synchronous> {
+        match self.pragma_value::<i64>("synchronous")? {
+            Some(0) => Ok(Synchronous::Off),
+            Some(1) => Ok(Synchronous::Normal),
+            Some(2) => Ok(Synchronous::Full),
+            Some(3) => Ok(Synchronous::Extra),
+            _ => raise!("failed to read the synchronization level"),
+        }
+    }
+
+    /// Set the synchronization level.
+    pub fn set_synchronous(&self, level: Synchronous) -> Result<()> {
+        let level = match level {
+            Synchronous::Off => 0,
+            Synchronous::Normal => 1,
+            Synchronous::Full => 2,
+            Synchronous::Extra => 3,
+        };
+        self.pragma("synchronous", level as i64)
+    }
+
+    /// Check if triggers can fire other triggers and themselves.
+    #[inline]
+    pub fn recursive_triggers(&self) -> Result<bool> {
+        self.pragma_value::<i64>("recursive_triggers")
+            .map(|value| value == Some(1))
+    }
+
+    /// Enable or disable recursive triggers, which are disabled by default.
+    ///
+    /// When enabled, a trigger can fire itself, and it is up to the trigger
+    /// to stop the recursion; otherwise, the statement fails once the depth
+    /// limit given by `Limit::TriggerDepth` is reached.
+    #[inline]
+    pub fn set_recursive_triggers(&self, enabled: bool) -> Result<()> {
+        self.pragma("recursive_triggers", enabled as i64)
+    }
+
+    /// Checkpoint the write-ahead logs of all attached databases.
+    ///
+    /// The function returns the number of frames in the log and the number of
+    /// frames checkpointed, both being 0 for databases not in the
+    /// write-ahead-log mode.
+    pub fn wal_checkpoint(&self, mode: WalCheckpointMode) -> Result<(usize, usize)> {
+        let mode = match mode {
+            WalCheckpointMode::Passive => ffi::SQLITE_CHECKPOINT_PASSIVE,
+            WalCheckpointMode::Full => ffi::SQLITE_CHECKPOINT_FULL,
+            WalCheckpointMode::Restart => ffi::SQLITE_CHECKPOINT_RESTART,
+            WalCheckpointMode::Truncate => ffi::SQLITE_CHECKPOINT_TRUNCATE,
+        };
+        let mut log_count = 0;
+        let mut checkpoint_count = 0;
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_wal_checkpoint_v2(
+                    self.raw.0,
+                    std::ptr::null(),
+                    mode,
+                    &mut log_count,
+                    &mut checkpoint_count,
+                )
+            );
+        }
+        Ok((log_count.max(0) as usize, checkpoint_count.max(0) as usize))
+    }
+
+    /// Set the number of pages in the write-ahead log after which the log is
+    /// checkpointed automatically on commit.
+    ///
+    /// The default is 1000 pages, and 0 disables automatic checkpoints. The
+    /// function replaces the callback set via `set_wal_hook` if any.
+    pub fn set_wal_autocheckpoint(&mut self, pages: usize) -> Result<()> {
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_wal_autocheckpoint(self.raw.0, pages as c_int)
+            );
+        }
+        self.wal_callback = None;
+        Ok(())
+    }
+
+    /// Rebuild the main database in place, reclaiming unused space.
+    ///
+    /// In the write-ahead-log mode, the rebuilt database goes through the log,
+    /// which can grow to the size of the database until it is checkpointed;
+    /// see `wal_checkpoint`. The function fails within a transaction.
+    #[inline]
+    pub fn vacuum(&self) -> Result<()> {
+        self.execute("VACUUM")
+    }
+
+    /// Write a compacted copy of the main database to a new file, leaving
+    /// the database itself untouched.
+    ///
+    /// The destination must not exist or be empty. The copy is a consistent
+    /// snapshot of the database, including the changes still in the
+    /// write-ahead log if any, and the log of the database is not
+    /// checkpointed. The function requires SQLite 3.27 or newer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let connection = sqlite::open("database.sqlite3")?;
+    /// connection.vacuum_into("backup.sqlite3")?;
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn vacuum_into<T: AsRef<Path>>(&self, path: T) -> Result<()> {
+        if ::version() < 3027000 {
+            raise!("vacuuming into a file requires SQLite 3.27 or newer");
+        }
+        let path = match path.as_ref().to_str() {
+            Some(path) => path,
+            _ => raise!("failed to process a path"),
+        };
+        let mut statement = self.prepare("VACUUM INTO ?")?;
+        statement.bind((1, path))?;
+        while let State::Row = statement.next()? {}
+        Ok(())
+    }
+
+    /// Record a snapshot of a database in the WAL mode.
+    ///
+    /// A read transaction has to be open on the database, and the snapshot
+    /// reflects the state seen by it. The function requires SQLite 3.22 or
+    /// newer compiled with `SQLITE_ENABLE_SNAPSHOT`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let connection = sqlite::open("database.sqlite3")?;
+    /// connection.execute("BEGIN")?;
+    /// connection.execute("SELECT * FROM sqlite_master")?;
+    /// let snapshot = connection.snapshot_get("main")?;
+    /// connection.execute("COMMIT")?;
+    ///
+    /// // Later, see the database as it was when the snapshot was taken.
+    /// connection.execute("BEGIN")?;
+    /// connection.snapshot_open("main", &snapshot)?;
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[cfg(feature = "snapshot")]
+    #[inline]
+    pub fn snapshot_get(&self, schema: &str) -> Result<Snapshot> {
+        ::snapshot::get(self.raw.0, schema)
+    }
+
+    /// Make the read transaction about to start see a database as captured in
+    /// a snapshot.
+    ///
+    /// The call has to be made within a transaction started with `BEGIN` that
+    /// has not yet read anything. The function fails if the snapshot is no
+    /// longer available, for instance, after a checkpoint has overwritten it.
+    #[cfg(feature = "snapshot")]
+    #[inline]
+    pub fn snapshot_open(&self, schema: &str, snapshot: &Snapshot) -> Result<()> {
+        ::snapshot::open(self.raw.0, schema, snapshot)
+    }
+
+    /// Make the snapshots of a database recorded by previous connections
+    /// available again after the last connection to it has been closed.
+    #[cfg(feature = "snapshot")]
+    #[inline]
+    pub fn snapshot_recover(&self, schema: &str) -> Result<()> {
+        ::snapshot::recover(self.raw.0, schema)
+    }
+
+    /// Return the conflict resolution mode of the virtual-table update in
+    /// progress.
+    ///
+    /// # Safety
+    ///
+    /// The function should only be called from within the `xUpdate` method of
+    /// a virtual table; SQLite reads out of bounds otherwise.
+    #[cfg(feature = "vtab")]
+    #[inline]
+    pub unsafe fn vtab_on_conflict(&self) -> ConflictMode {
+        ConflictMode::from_raw(ffi::sqlite3_vtab_on_conflict(self.raw.0))
+    }
+
+    /// Serialize a database into a sequence of bytes.
+    ///
+    /// The schema defaults to `main` when `None` is given. The function
+    /// requires SQLite 3.36 or newer, or SQLite 3.23 or newer compiled with
+    /// `SQLITE_ENABLE_DESERIALIZE`.
+    #[cfg(feature = "serialize")]
+    pub fn serialize(&self, schema: Option<&str>) -> Result<Vec<u8>> {
+        let schema = str_to_cstr!(schema.unwrap_or("main"));
+        unsafe {
+            let mut size = 0;
+            let pointer = sqlite3_serialize(self.raw.0, schema.as_ptr(), &mut size, 0);
+            if pointer.is_null() {
+                raise!("failed to serialize the database");
+            }
+            let data = std::slice::from_raw_parts(pointer, size as usize).to_vec();
+            ffi::sqlite3_free(pointer as *mut _);
+            Ok(data)
+        }
+    }
+
+    /// Replace the content of a database with a sequence of bytes produced by
+    /// `serialize`.
+    ///
+    /// The schema defaults to `main` when `None` is given. The resulting
+    /// database is kept in memory and can grow. See `serialize` for the
+    /// required version of SQLite.
+    #[cfg(feature = "serialize")]
+    pub fn deserialize(&mut self, schema: Option<&str>, data: Vec<u8>) -> Result<()> {
+        let schema = str_to_cstr!(schema.unwrap_or("main"));
+        unsafe {
+            let size = data.len();
+            let pointer = ffi::sqlite3_malloc64(size.max(1) as ffi::sqlite3_uint64) as *mut u8;
+            if pointer.is_null() {
+                raise!("failed to allocate memory");
+            }
+            std::ptr::copy_nonoverlapping(data.as_ptr(), pointer, size);
+            ok!(
+                self.raw.0,
+                sqlite3_deserialize(
+                    self.raw.0,
+                    schema.as_ptr(),
+                    pointer,
+                    size as ffi::sqlite3_int64,
+                    size as ffi::sqlite3_int64,
+                    SQLITE_DESERIALIZE_FREEONCLOSE | SQLITE_DESERIALIZE_RESIZABLE,
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Copy a database into another connection in one go.
+    ///
+    /// The schema is used on both sides, with `main` standing for the main
+    /// database.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let source = sqlite::open(":memory:")?;
+    /// source.execute("CREATE TABLE users (name TEXT)")?;
+    ///
+    /// let mut destination = sqlite::open(":memory:")?;
+    /// source.backup_to(&mut destination, "main")?;
+    /// destination.execute("SELECT * FROM users")?;
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn backup_to(&self, destination: &mut Connection, schema: &str) -> Result<()> {
+        let mut backup = ::backup::new(self, destination, schema)?;
+        if !backup.step(-1)?.done {
+            raise!("failed to complete the backup as a database is busy");
+        }
+        backup.finish()
+    }
+
+    /// Start copying the main database into another connection
+    /// incrementally.
+    #[inline]
+    pub fn backup_init<'l>(&'l self, destination: &'l mut Connection) -> Result<BackupHandle<'l>> {
+        ::backup::new(self, destination, "main")
+    }
+
+    /// Open a BLOB in the main database for incremental I/O.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE photos (data BLOB)")?;
+    /// connection.execute("INSERT INTO photos VALUES (X'4269')")?;
+    ///
+    /// let mut data = vec![];
+    /// let mut blob = connection.open_blob("photos", "data", 1, false)?;
+    /// blob.read_to_end(&mut data).unwrap();
+    /// assert_eq!(data, vec![0x42, 0x69]);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn open_blob(
+        &self,
+        table: &str,
+        column: &str,
+        rowid: i64,
+        write: bool,
+    ) -> Result<BlobHandle<'_>> {
+        ::blob::new(self, table, column, rowid, write)
+    }
+
+    /// Set a callback for authorizing actions while statements are being
+    /// prepared.
+    ///
+    /// An action is denied if the callback panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{Authorization, AuthorizerAction};
+    ///
+    /// let mut connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE secrets (value TEXT)")?;
+    /// connection.set_authorizer(|action| match action {
+    ///     AuthorizerAction::ReadColumn { table: "secrets", .. } => Authorization::Deny,
+    ///     _ => Authorization::Ok,
+    /// })?;
+    /// assert!(connection.prepare("SELECT * FROM secrets").is_err());
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn set_authorizer<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: FnMut(AuthorizerAction<'_>) -> Authorization + Send + 'static,
+    {
+        unsafe {
+            let callback = Box::new(callback);
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_set_authorizer(
+                    self.raw.0,
+                    Some(::authorizer::callback::<F>),
+                    &*callback as *const F as *mut F as *mut _,
+                )
+            );
+            self.authorizer_callback = Some(callback);
+        }
+        Ok(())
+    }
+
+    /// Set a callback for handling busy events.
+    ///
+    /// The callback is triggered when the database cannot perform an operation
+    /// due to processing of some other request. If the callback returns `true`,
+    /// the operation will be repeated.
+    pub fn set_busy_handler<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: FnMut(usize) -> bool + Send + 'static,
+    {
+        self.remove_busy_handler()?;
+        unsafe {
+            let callback = Box::new(callback);
+            let result = ffi::sqlite3_busy_handler(
+                self.raw.0,
+                Some(busy_callback::<F>),
+                &*callback as *const F as *mut F as *mut _,
+            );
+            self.busy_callback = Some(callback);
+            ok!(self.raw.0, result);
+        }
+        Ok(())
+    }
+
+    /// Set an implicit callback for handling busy events that tries to repeat
+    /// rejected operations until a timeout expires.
+    #[inline]
+    pub fn set_busy_timeout(&mut self, milliseconds: usize) -> Result<()> {
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_busy_timeout(self.raw.0, milliseconds as c_int)
+            );
+        }
+        Ok(())
+    }
+
+    /// Set a callback for tracking commits.
+    ///
+    /// The callback is triggered whenever a transaction is about to be
+    /// committed. If the callback returns `false` or panics, the transaction
+    /// is rolled back instead. The callback should not modify the database.
+    pub fn set_commit_hook<F>(&mut self, callback: F)
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        unsafe {
+            let callback = Box::new(callback);
+            ffi::sqlite3_commit_hook(
+                self.raw.0,
+                Some(commit_callback::<F>),
+                &*callback as *const F as *mut F as *mut _,
+            );
+            self.commit_callback = Some(callback);
+        }
+    }
+
+    /// Set a callback for tracking the progress of long-running operations.
+    ///
+    /// The callback is triggered approximately every given number of virtual
+    /// machine instructions. If the callback returns `false` or panics, the
+    /// current operation is interrupted, and it fails with an interruption
+    /// error.
+    pub fn set_progress_handler<F>(&mut self, instructions: u32, callback: F) -> Result<()>
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        if instructions > c_int::MAX as u32 {
+            raise!("the number of instructions is too large ({})", instructions);
+        }
+        unsafe {
+            let callback = Box::new(callback);
+            ffi::sqlite3_progress_handler(
+                self.raw.0,
+                instructions as c_int,
+                Some(progress_callback::<F>),
+                &*callback as *const F as *mut F as *mut _,
+            );
+            self.progress_callback = Some(callback);
+        }
+        Ok(())
+    }
+
+    /// Set a callback for tracking rollbacks.
+    ///
+    /// The callback is triggered whenever a transaction is rolled back, which
+    /// includes rollbacks caused by the commit hook. The callback should not
+    /// modify the database.
+    pub fn set_rollback_hook<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        unsafe {
+            let callback = Box::new(callback);
+            ffi::sqlite3_rollback_hook(
+                self.raw.0,
+                Some(rollback_callback::<F>),
+                &*callback as *const F as *mut F as *mut _,
+            );
+            self.rollback_callback = Some(callback);
+        }
+    }
+
+    /// Set a callback for tracing the events selected by the flags.
+    ///
+    /// The callback is triggered for each event, such as the start of a
+    /// statement or the production of a row. The callback should not modify
+    /// the database.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{TraceEvent, TraceFlags};
+    ///
+    /// let mut connection = sqlite::open(":memory:")?;
+    /// connection.set_trace_hook(TraceFlags::new().set_profile(), |event| {
+    ///     if let TraceEvent::Profile { sql, nanoseconds } = event {
+    ///         println!("{} took {} ns", sql, nanoseconds);
+    ///     }
+    /// })?;
+    /// connection.execute("CREATE TABLE users (name TEXT)")?;
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn set_trace_hook<F>(&mut self, flags: TraceFlags, callback: F) -> Result<()>
+    where
+        F: FnMut(TraceEvent<'_>) + Send + 'static,
+    {
+        unsafe {
+            let callback = Box::new(callback);
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_trace_v2(
+                    self.raw.0,
+                    ::trace::flags(flags),
+                    Some(::trace::callback::<F>),
+                    &*callback as *const F as *mut F as *mut _,
+                )
+            );
+            self.trace_callback = Some(callback);
+        }
+        Ok(())
+    }
+
+    /// Set a callback for tracking row changes.
+    ///
+    /// The callback is triggered for each row inserted, updated, or deleted in
+    /// a rowid table and receives the kind of the change, the database name,
+    /// the table name, and the rowid of the affected row. The callback should
+    /// not modify the database.
+    pub fn set_update_hook<F>(&mut self, callback: F)
+    where
+        F: FnMut(Action, &str, &str, i64) + Send + 'static,
+    {
+        unsafe {
+            let callback = Box::new(callback);
+            ffi::sqlite3_update_hook(
+                self.raw.0,
+                Some(update_callback::<F>),
+                &*callback as *const F as *mut F as *mut _,
+            );
+            self.update_callback = Some(callback);
+        }
+    }
+
+    /// Set a callback for tracking commits in the write-ahead-log mode.
+    ///
+    /// The callback is triggered after each commit and receives the database
+    /// name and the number of pages in the log. Setting the callback disables
+    /// automatic checkpoints; see `set_wal_autocheckpoint`.
+    pub fn set_wal_hook<F>(&mut self, callback: F)
+    where
+        F: FnMut(&str, usize) + Send + 'static,
+    {
+        unsafe {
+            let callback = Box::new(callback);
+            ffi::sqlite3_wal_hook(
+                self.raw.0,
+                Some(wal_callback::<F>),
+                &*callback as *const F as *mut F as *mut _,
+            );
+            self.wal_callback = Some(callback);
+        }
+    }
+
+    /// Remove the callback authorizing actions.
+    #[inline]
+    pub fn remove_authorizer(&mut self) -> Result<()> {
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_set_authorizer(self.raw.0, None, std::ptr::null_mut())
+            );
+        }
+        self.authorizer_callback = None;
+        Ok(())
+    }
+
+    /// Remove the callback handling busy events.
+    #[inline]
+    pub fn remove_busy_handler(&mut self) -> Result<()> {
+        self.busy_callback = None;
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_busy_handler(self.raw.0, None, std::ptr::null_mut())
+            );
+        }
+        Ok(())
+    }
+
+    /// Remove the callback tracking commits.
+    #[inline]
+    pub fn remove_commit_hook(&mut self) {
+        unsafe { ffi::sqlite3_commit_hook(self.raw.0, None, std::ptr::null_mut()) };
+        self.commit_callback = None;
+    }
+
+    /// Remove the callback tracking progress.
+    #[inline]
+    pub fn remove_progress_handler(&mut self) -> Result<()> {
+        unsafe { ffi::sqlite3_progress_handler(self.raw.0, 0, None, std::ptr::null_mut()) };
+        self.progress_callback = None;
+        Ok(())
+    }
+
+    /// Remove the callback tracking rollbacks.
+    #[inline]
+    pub fn remove_rollback_hook(&mut self) {
+        unsafe { ffi::sqlite3_rollback_hook(self.raw.0, None, std::ptr::null_mut()) };
+        self.rollback_callback = None;
+    }
+
+    /// Remove the callback tracing events.
+    #[inline]
+    pub fn remove_trace_hook(&mut self) -> Result<()> {
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_trace_v2(self.raw.0, 0, None, std::ptr::null_mut())
+            );
+        }
+        self.trace_callback = None;
+        Ok(())
+    }
+
+    /// Remove the callback tracking row changes.
+    #[inline]
+    pub fn remove_update_hook(&mut self) {
+        unsafe { ffi::sqlite3_update_hook(self.raw.0, None, std::ptr::null_mut()) };
+        self.update_callback = None;
+    }
+
+    /// Remove the callback tracking commits in the write-ahead-log mode.
+    ///
+    /// Automatic checkpoints remain disabled until re-enabled via
+    /// `set_wal_autocheckpoint`.
+    #[inline]
+    pub fn remove_wal_hook(&mut self) {
+        unsafe { ffi::sqlite3_wal_hook(self.raw.0, None, std::ptr::null_mut()) };
+        self.wal_callback = None;
+    }
+
+    /// Set a callback to be invoked once the connection blocking this one in
+    /// the shared-cache mode has finished its transaction.
+    ///
+    /// The callback is invoked right away if the connection is not blocked.
+    /// Otherwise, it is invoked from within the thread of the blocking
+    /// connection, and it should only signal to the waiting thread without
+    /// using SQLite. A previously set callback that has not been invoked yet
+    /// is canceled. The function fails with the code `SQLITE_LOCKED` if
+    /// waiting would lead to a deadlock, in which case the previous callback
+    /// remains in place.
+    pub fn unlock_notify<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let callback: Box<UnlockNotifyCallback> = Box::new(Mutex::new(Some(Box::new(callback))));
+        unsafe {
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_unlock_notify(
+                    self.raw.0,
+                    Some(unlock_notify_callback),
+                    &*callback as *const UnlockNotifyCallback as *mut _,
+                )
+            );
+        }
+        self.unlock_notify_callback = Some(callback);
+        Ok(())
+    }
+
+    /// Create a scalar function callable from SQL statements.
+    ///
+    /// The function is identified by its name and number of arguments, with
+    /// -1 meaning that any number of arguments is accepted. An existing
+    /// function with the same name and number of arguments is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{FunctionFlags, State, Value};
+    ///
+    /// let mut connection = sqlite::open(":memory:")?;
+    /// let flags = FunctionFlags::new().set_deterministic();
+    /// connection.create_scalar_function("double", 1, flags, |values| match values[0] {
+    ///     Value::Integer(value) => Value::Integer(2 * value),
+    ///     _ => Value::Null,
+    /// })?;
+    ///
+    /// let mut statement = connection.prepare("SELECT double(21)")?;
+    /// assert_eq!(statement.next()?, State::Row);
+    /// assert_eq!(statement.read::<i64, _>(0)?, 42);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn create_scalar_function<F>(
+        &mut self,
+        name: &str,
+        argument_count: i32,
+        flags: FunctionFlags,
+        function: F,
+    ) -> Result<()>
+    where
+        F: Fn(&[Value]) -> Value + Send + 'static,
+    {
+        ::function::create_scalar(self.raw.0, name, argument_count, flags, function)
+    }
+
+    /// Create a scalar function with access to the context of each call.
+    ///
+    /// In addition to the arguments, the context provides access to values
+    /// passed via `Statement::bind_pointer` when the `pointer` feature is
+    /// enabled. See `create_scalar_function` for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{FunctionFlags, State, Value};
+    ///
+    /// let mut connection = sqlite::open(":memory:")?;
+    /// let flags = FunctionFlags::new();
+    /// connection.create_scalar_function_with_context("arity", -1, flags, |context| {
+    ///     Value::Integer(context.values().len() as i64)
+    /// })?;
+    ///
+    /// let mut statement = connection.prepare("SELECT arity(1, 2, 3)")?;
+    /// assert_eq!(statement.next()?, State::Row);
+    /// assert_eq!(statement.read::<i64, _>(0)?, 3);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn create_scalar_function_with_context<F>(
+        &mut self,
+        name: &str,
+        argument_count: i32,
+        flags: FunctionFlags,
+        function: F,
+    ) -> Result<()>
+    where
+        F: Fn(&FunctionContext<'_>) -> Value + Send + 'static,
+    {
+        ::function::create_scalar_with_context(self.raw.0, name, argument_count, flags, function)
+    }
+
+    /// Create an aggregate function callable from SQL statements.
+    ///
+    /// The function is identified by its name and number of arguments, with
+    /// -1 meaning that any number of arguments is accepted. An existing
+    /// function with the same name and number of arguments is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{Aggregate, State, Value};
+    ///
+    /// #[derive(Default)]
+    /// struct Product(i64);
+    ///
+    /// impl Aggregate for Product {
+    ///     fn step(&mut self, values: &[Value]) {
+    ///         self.0 = self.0.max(1) * values[0].try_into::<i64>().unwrap_or(1);
+    ///     }
+    ///
+    ///     fn finalize(self) -> Value {
+    ///         Value::Integer(self.0)
+    ///     }
+    /// }
+    ///
+    /// let mut connection = sqlite::open(":memory:")?;
+    /// connection.create_aggregate_function::<Product>("product", 1)?;
+    ///
+    /// let query = "SELECT product(value) FROM (SELECT 2 AS value UNION SELECT 3)";
+    /// let mut statement = connection.prepare(query)?;
+    /// assert_eq!(statement.next()?, State::Row);
+    /// assert_eq!(statement.read::<i64, _>(0)?, 6);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn create_aggregate_function<A>(&mut self, name: &str, argument_count: i32) -> Result<()>
+    where
+        A: Aggregate,
+    {
+        ::function::create_aggregate::<A>(self.raw.0, name, argument_count)
+    }
+
+    /// Create an aggregate window function callable from SQL statements.
+    ///
+    /// The function can be used both as an ordinary aggregate function and as
+    /// a window function. See `create_aggregate_function` for the naming
+    /// rules. The function requires SQLite 3.25 or newer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{Aggregate, Value, WindowAggregate};
+    ///
+    /// #[derive(Default)]
+    /// struct Sum(i64);
+    ///
+    /// impl Aggregate for Sum {
+    ///     fn step(&mut self, values: &[Value]) {
+    ///         self.0 += values[0].try_into::<i64>().unwrap_or(0);
+    ///     }
+    ///
+    ///     fn finalize(self) -> Value {
+    ///         Value::Integer(self.0)
+    ///     }
+    /// }
+    ///
+    /// impl WindowAggregate for Sum {
+    ///     fn inverse(&mut self, values: &[Value]) {
+    ///         self.0 -= values[0].try_into::<i64>().unwrap_or(0);
+    ///     }
+    ///
+    ///     fn value(&self) -> Value {
+    ///         Value::Integer(self.0)
+    ///     }
+    /// }
+    ///
+    /// let mut connection = sqlite::open(":memory:")?;
+    /// connection.create_window_function::<Sum>("moving_sum", 1)?;
+    ///
+    /// let query = "
+    ///     SELECT moving_sum(value) OVER (ROWS 1 PRECEDING)
+    ///     FROM (SELECT 1 AS value UNION ALL SELECT 2 UNION ALL SELECT 3)
+    /// ";
+    /// let mut statement = connection.prepare(query)?;
+    /// let sums = statement
+    ///     .iter()
+    ///     .map(|row| row.map(|row| row.read::<i64, _>(0)))
+    ///     .collect::<sqlite::Result<Vec<_>>>()?;
+    /// assert_eq!(sums, vec![1, 3, 5]);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[cfg(feature = "window")]
+    #[inline]
+    pub fn create_window_function<A>(&mut self, name: &str, argument_count: i32) -> Result<()>
+    where
+        A: WindowAggregate,
+    {
+        ::function::create_window::<A>(self.raw.0, name, argument_count)
+    }
+
+    /// Remove a function.
+    #[inline]
+    pub fn remove_function(&mut self, name: &str, argument_count: i32) -> Result<()> {
+        ::function::remove(self.raw.0, name, argument_count)
+    }
+
+    /// Create a collation for ordering text.
+    ///
+    /// An existing collation with the same name is replaced. Collations can
+    /// also be created lazily in the callback given to `collation_needed`. If
+    /// the function panics, the strings are considered equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.create_collation("reverse", |first, second| second.cmp(first))?;
+    ///
+    /// let query = "SELECT 'a' AS value UNION SELECT 'b' ORDER BY 1 COLLATE reverse LIMIT 1";
+    /// let value = connection.query_row(query, (), |row| {
+    ///     row.try_read::<&str, _>(0).map(str::to_owned)
+    /// })?;
+    /// assert_eq!(value, "b");
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn create_collation<F>(&self, name: &str, function: F) -> Result<()>
+    where
+        F: Fn(&str, &str) -> Ordering + Send + 'static,
+    {
+        ::function::create_collation(self.raw.0, name, function)
+    }
+
+    /// Remove a collation.
+    #[inline]
+    pub fn remove_collation(&self, name: &str) -> Result<()> {
+        ::function::remove_collation(self.raw.0, name)
+    }
+
+    /// Set a callback for creating collations when they are first needed.
+    ///
+    /// The callback receives a handle to the connection and the name of the
+    /// missing collation, which it can then create via `create_collation`.
+    pub fn collation_needed<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: FnMut(&CollationHandle<'_>, &str) + Send + 'static,
+    {
+        unsafe {
+            let callback = Box::new(callback);
+            ok!(
+                self.raw.0,
+                ffi::sqlite3_collation_needed(
+                    self.raw.0,
+                    &*callback as *const F as *mut F as *mut _,
+                    Some(collation_callback::<F>),
+                )
+            );
+            self.collation_callback = Some(callback);
+        }
+        Ok(())
+    }
+
+    fn check(&self, pragma: &str) -> Result<Vec<String>> {
+        let mut statement = self.prepare(format!("PRAGMA {}", pragma))?;
+        let mut problems = vec![];
+        while let State::Row = statement.next()? {
+            problems.push(statement.read::<String, _>(0)?);
+        }
+        if problems.len() == 1 && problems[0] == "ok" {
+            problems.clear();
+        }
+        Ok(problems)
+    }
+
+    fn schema_names(&self, kind: &str, table: Option<&str>) -> Result<Vec<String>> {
+        let query = "
+            SELECT name FROM sqlite_master
+            WHERE type = ?1 AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\'
+            AND (?2 IS NULL OR tbl_name = ?2 COLLATE NOCASE)
+            ORDER BY name
+        ";
+        let mut statement = self.prepare(query)?;
+        statement.bind((1, kind))?;
+        statement.bind((2, table))?;
+        let mut names = vec![];
+        while let State::Row = statement.next()? {
+            names.push(statement.read::<String, _>(0)?);
+        }
+        Ok(names)
+    }
+
+    fn open_with_flags_and_vfs(
+        path: &Path,
+        flags: OpenFlags,
+        vfs: Option<&str>,
+    ) -> Result<Connection> {
+        if flags.0 & SQLITE_OPEN_NOFOLLOW != 0 && ::version() < 3031000 {
+            raise!("the nofollow flag requires SQLite 3.31 or newer");
+        }
+        let vfs = match vfs {
+            Some(vfs) => Some(str_to_cstr!(vfs)),
+            _ => None,
+        };
         let mut raw = std::ptr::null_mut();
         unsafe {
             let code = ffi::sqlite3_open_v2(
-                path_to_cstr!(path.as_ref()).as_ptr(),
+                path_to_cstr!(path).as_ptr(),
                 &mut raw,
                 flags.0,
-                std::ptr::null(),
+                vfs.as_ref().map_or(std::ptr::null(), |vfs| vfs.as_ptr()),
             );
             match code {
                 ffi::SQLITE_OK => {}
@@ -50,157 +2376,249 @@ impl Connection {
                         ffi::sqlite3_close(raw);
                         return Err(::Error {
                             code: Some(code as isize),
+                            extended_code: None,
                             message: None,
                         });
                     }
                 },
             }
         }
-        Ok(Connection {
+        Ok(Connection::from_raw(raw))
+    }
+
+    fn from_raw(raw: *mut ffi::sqlite3) -> Connection {
+        Connection {
             raw: Raw(raw),
+            authorizer_callback: None,
             busy_callback: None,
+            collation_callback: None,
+            commit_callback: None,
+            progress_callback: None,
+            rollback_callback: None,
+            trace_callback: None,
+            update_callback: None,
+            unlock_notify_callback: None,
+            wal_callback: None,
+            statement_cache: Mutex::new(StatementCache::new(STATEMENT_CACHE_CAPACITY)),
             phantom: PhantomData,
-        })
+        }
     }
 
-    /// Open a thread-safe read-write connection to a new or existing database.
-    pub fn open_with_full_mutex<T: AsRef<Path>>(path: T) -> Result<ConnectionWithFullMutex> {
-        Connection::open_with_flags(
-            path,
-            OpenFlags::new()
-                .set_create()
-                .set_read_write()
-                .set_full_mutex(),
-        )
-        .map(ConnectionWithFullMutex)
+    #[doc(hidden)]
+    #[inline]
+    pub fn as_raw(&self) -> *mut ffi::sqlite3 {
+        self.raw.0
+    }
+}
+
+impl std::fmt::Debug for Connection {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let path = self
+            .db_filename("main")
+            .unwrap_or_else(|| PathBuf::from(":memory:"));
+        formatter
+            .debug_struct("Connection")
+            .field("path", &path)
+            .finish()
     }
+}
 
-    /// Execute a statement without processing the resulting rows if any.
+impl Drop for Connection {
     #[inline]
-    pub fn execute<T: AsRef<str>>(&self, statement: T) -> Result<()> {
-        unsafe {
-            ok!(
-                self.raw.0,
-                ffi::sqlite3_exec(
-                    self.raw.0,
-                    str_to_cstr!(statement.as_ref()).as_ptr(),
-                    None,
-                    std::ptr::null_mut(),
-                    std::ptr::null_mut(),
-                )
-            );
-        }
-        Ok(())
+    #[allow(unused_must_use)]
+    fn drop(&mut self) {
+        statement_cache(self).clear();
+        self.remove_busy_handler();
+        unsafe { ffi::sqlite3_close(self.raw.0) };
     }
+}
 
-    /// Execute a statement and process the resulting rows as plain text.
-    ///
-    /// The callback is triggered for each row. If the callback returns `false`,
-    /// no more rows will be processed. For large queries and non-string data
-    /// types, prepared statement are highly preferable; see `prepare`.
+impl ConnectionBuilder {
+    /// Create a builder.
     #[inline]
-    pub fn iterate<T: AsRef<str>, F>(&self, statement: T, callback: F) -> Result<()>
-    where
-        F: FnMut(&[(&str, Option<&str>)]) -> bool,
-    {
-        unsafe {
-            let callback = Box::new(callback);
-            ok!(
-                self.raw.0,
-                ffi::sqlite3_exec(
-                    self.raw.0,
-                    str_to_cstr!(statement.as_ref()).as_ptr(),
-                    Some(process_callback::<F>),
-                    &*callback as *const F as *mut F as *mut _,
-                    std::ptr::null_mut(),
-                )
-            );
+    pub fn new() -> Self {
+        ConnectionBuilder {
+            path: None,
+            flags: OpenFlags::new().set_read_write(),
+            busy_timeout: None,
+            foreign_keys: None,
+            journal_mode: None,
         }
-        Ok(())
     }
 
-    /// Create a prepared statement.
-    #[inline]
-    pub fn prepare<T: AsRef<str>>(&self, statement: T) -> Result<Statement<'_>> {
-        ::statement::new(self.raw.0, statement)
+    /// Set the path to the database.
+    pub fn path<T: AsRef<Path>>(mut self, path: T) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
     }
 
-    /// Return the number of rows inserted, updated, or deleted by the most
-    /// recent INSERT, UPDATE, or DELETE statement.
-    #[inline]
-    pub fn change_count(&self) -> usize {
-        unsafe { ffi::sqlite3_changes(self.raw.0) as usize }
+    /// Open the database for reading only.
+    ///
+    /// Opening fails if `create` is also called.
+    pub fn read_only(mut self) -> Self {
+        self.flags.0 &= !ffi::SQLITE_OPEN_READWRITE;
+        self.flags = self.flags.set_read_only();
+        self
     }
 
-    /// Return the total number of rows inserted, updated, and deleted by all
-    /// INSERT, UPDATE, and DELETE statements since the connection was opened.
-    #[inline]
-    pub fn total_change_count(&self) -> usize {
-        unsafe { ffi::sqlite3_total_changes(self.raw.0) as usize }
+    /// Create the database if it does not exist.
+    ///
+    /// Opening fails if `read_only` is also called.
+    pub fn create(mut self) -> Self {
+        self.flags = self.flags.set_create();
+        self
     }
 
-    /// Set a callback for handling busy events.
+    /// Open the database in memory; see `OpenFlags::set_memory`.
+    pub fn memory(mut self) -> Self {
+        self.flags = self.flags.set_memory();
+        self
+    }
+
+    /// Interpret the path as a URI filename; see `OpenFlags::set_uri`.
+    pub fn uri(mut self) -> Self {
+        self.flags = self.flags.set_uri();
+        self
+    }
+
+    /// Set the busy timeout in milliseconds.
     ///
-    /// The callback is triggered when the database cannot perform an operation
-    /// due to processing of some other request. If the callback returns `true`,
-    /// the operation will be repeated.
-    pub fn set_busy_handler<F>(&mut self, callback: F) -> Result<()>
-    where
-        F: FnMut(usize) -> bool + Send + 'static,
-    {
-        self.remove_busy_handler()?;
-        unsafe {
-            let callback = Box::new(callback);
-            let result = ffi::sqlite3_busy_handler(
-                self.raw.0,
-                Some(busy_callback::<F>),
-                &*callback as *const F as *mut F as *mut _,
-            );
-            self.busy_callback = Some(callback);
-            ok!(self.raw.0, result);
-        }
-        Ok(())
+    /// See `Connection::set_busy_timeout` for details.
+    pub fn busy_timeout(mut self, milliseconds: usize) -> Self {
+        self.busy_timeout = Some(milliseconds);
+        self
     }
 
-    /// Set an implicit callback for handling busy events that tries to repeat
-    /// rejected operations until a timeout expires.
+    /// Enable or disable the enforcement of foreign-key constraints.
+    pub fn foreign_keys(mut self, enabled: bool) -> Self {
+        self.foreign_keys = Some(enabled);
+        self
+    }
+
+    /// Set the journal mode.
+    ///
+    /// Opening fails if the mode cannot be set, which is the case, for
+    /// instance, for the write-ahead-log mode and in-memory databases.
+    pub fn journal_mode(mut self, mode: JournalMode) -> Self {
+        self.journal_mode = Some(mode);
+        self
+    }
+
+    /// Set the journal mode to write-ahead log.
+    ///
+    /// See `journal_mode` for details.
     #[inline]
-    pub fn set_busy_timeout(&mut self, milliseconds: usize) -> Result<()> {
-        unsafe {
-            ok!(
-                self.raw.0,
-                ffi::sqlite3_busy_timeout(self.raw.0, milliseconds as c_int)
-            );
+    pub fn wal_mode(self) -> Self {
+        self.journal_mode(JournalMode::Wal)
+    }
+
+    /// Open the connection.
+    pub fn open(self) -> Result<Connection> {
+        let path = match self.path {
+            Some(ref path) => path,
+            _ => raise!("the path should be set"),
+        };
+        if self.flags.0 & ffi::SQLITE_OPEN_READONLY != 0
+            && self.flags.0 & ffi::SQLITE_OPEN_CREATE != 0
+        {
+            raise!("a database cannot be both read-only and created");
         }
-        Ok(())
+        let mut connection = Connection::open_with_flags(path, self.flags)?;
+        if let Some(milliseconds) = self.busy_timeout {
+            connection.set_busy_timeout(milliseconds)?;
+        }
+        if let Some(enabled) = self.foreign_keys {
+            connection.enable_foreign_keys(enabled)?;
+        }
+        if let Some(mode) = self.journal_mode {
+            if connection.set_journal_mode(mode)? != mode {
+                raise!("failed to set the journal mode ({})", mode.as_str());
+            }
+        }
+        Ok(connection)
     }
+}
 
-    /// Remove the callback handling busy events.
+impl Default for ConnectionBuilder {
     #[inline]
-    pub fn remove_busy_handler(&mut self) -> Result<()> {
-        self.busy_callback = None;
-        unsafe {
-            ok!(
-                self.raw.0,
-                ffi::sqlite3_busy_handler(self.raw.0, None, std::ptr::null_mut())
-            );
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "vtab")]
+impl ConflictMode {
+    fn from_raw(mode: c_int) -> Self {
+        match mode {
+            ffi::SQLITE_ROLLBACK => ConflictMode::Rollback,
+            ffi::SQLITE_FAIL => ConflictMode::Fail,
+            ffi::SQLITE_IGNORE => ConflictMode::Ignore,
+            ffi::SQLITE_REPLACE => ConflictMode::Replace,
+            _ => ConflictMode::Abort,
         }
-        Ok(())
     }
+}
 
-    #[doc(hidden)]
-    #[inline]
-    pub fn as_raw(&self) -> *mut ffi::sqlite3 {
-        self.raw.0
+impl DbStatus {
+    fn as_raw(&self) -> c_int {
+        match self {
+            DbStatus::LookasideUsed => ffi::SQLITE_DBSTATUS_LOOKASIDE_USED,
+            DbStatus::CacheUsed => ffi::SQLITE_DBSTATUS_CACHE_USED,
+            DbStatus::SchemaUsed => ffi::SQLITE_DBSTATUS_SCHEMA_USED,
+            DbStatus::StmtUsed => ffi::SQLITE_DBSTATUS_STMT_USED,
+            DbStatus::LookasideHit => ffi::SQLITE_DBSTATUS_LOOKASIDE_HIT,
+            DbStatus::LookasideMissSize => ffi::SQLITE_DBSTATUS_LOOKASIDE_MISS_SIZE,
+            DbStatus::LookasideMissFull => ffi::SQLITE_DBSTATUS_LOOKASIDE_MISS_FULL,
+            DbStatus::CacheHit => ffi::SQLITE_DBSTATUS_CACHE_HIT,
+            DbStatus::CacheMiss => ffi::SQLITE_DBSTATUS_CACHE_MISS,
+            DbStatus::CacheWrite => ffi::SQLITE_DBSTATUS_CACHE_WRITE,
+            DbStatus::DeferredFks => ffi::SQLITE_DBSTATUS_DEFERRED_FKS,
+            DbStatus::CacheUsedShared => ffi::SQLITE_DBSTATUS_CACHE_USED_SHARED,
+        }
     }
 }
 
-impl Drop for Connection {
-    #[inline]
-    #[allow(unused_must_use)]
-    fn drop(&mut self) {
-        self.remove_busy_handler();
-        unsafe { ffi::sqlite3_close(self.raw.0) };
+impl JournalMode {
+    fn parse(mode: &str) -> Result<Self> {
+        Ok(match mode.to_lowercase().as_str() {
+            "delete" => JournalMode::Delete,
+            "truncate" => JournalMode::Truncate,
+            "persist" => JournalMode::Persist,
+            "memory" => JournalMode::Memory,
+            "wal" => JournalMode::Wal,
+            "off" => JournalMode::Off,
+            _ => raise!("failed to recognize a journal mode ({})", mode),
+        })
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            JournalMode::Delete => "DELETE",
+            JournalMode::Truncate => "TRUNCATE",
+            JournalMode::Persist => "PERSIST",
+            JournalMode::Memory => "MEMORY",
+            JournalMode::Wal => "WAL",
+            JournalMode::Off => "OFF",
+        }
+    }
+}
+
+impl Limit {
+    fn as_raw(&self) -> c_int {
+        match self {
+            Limit::Length => ffi::SQLITE_LIMIT_LENGTH,
+            Limit::SqlLength => ffi::SQLITE_LIMIT_SQL_LENGTH,
+            Limit::Column => ffi::SQLITE_LIMIT_COLUMN,
+            Limit::ExprDepth => ffi::SQLITE_LIMIT_EXPR_DEPTH,
+            Limit::CompoundSelect => ffi::SQLITE_LIMIT_COMPOUND_SELECT,
+            Limit::VdbeOp => ffi::SQLITE_LIMIT_VDBE_OP,
+            Limit::FunctionArg => ffi::SQLITE_LIMIT_FUNCTION_ARG,
+            Limit::Attached => ffi::SQLITE_LIMIT_ATTACHED,
+            Limit::LikePatternLength => ffi::SQLITE_LIMIT_LIKE_PATTERN_LENGTH,
+            Limit::VariableNumber => ffi::SQLITE_LIMIT_VARIABLE_NUMBER,
+            Limit::TriggerDepth => ffi::SQLITE_LIMIT_TRIGGER_DEPTH,
+            Limit::WorkerThreads => ffi::SQLITE_LIMIT_WORKER_THREADS,
+        }
     }
 }
 
@@ -217,6 +2635,17 @@ impl OpenFlags {
         self
     }
 
+    /// Report [extended result codes][1] in place of primary ones.
+    ///
+    /// The flag requires SQLite 3.37 or newer and is ignored by older
+    /// versions, in which case only primary result codes are reported.
+    ///
+    /// [1]: https://www.sqlite.org/rescode.html#extrc
+    pub fn set_exrescode(mut self) -> Self {
+        self.0 |= SQLITE_OPEN_EXRESCODE;
+        self
+    }
+
     /// Open the database in the serialized [threading mode][1].
     ///
     /// [1]: https://www.sqlite.org/threadsafe.html
@@ -225,6 +2654,27 @@ impl OpenFlags {
         self
     }
 
+    /// Open the database in memory.
+    ///
+    /// The path is not treated as a filename but as the name of the database,
+    /// which connections opened in [shared-cache mode][1] use to refer to the
+    /// same in-memory database.
+    ///
+    /// [1]: https://www.sqlite.org/sharedcache.html
+    pub fn set_memory(mut self) -> Self {
+        self.0 |= ffi::SQLITE_OPEN_MEMORY;
+        self
+    }
+
+    /// Refuse to open the database if the path is a symbolic link.
+    ///
+    /// The flag requires SQLite 3.31 or newer; opening fails with older
+    /// versions.
+    pub fn set_nofollow(mut self) -> Self {
+        self.0 |= SQLITE_OPEN_NOFOLLOW;
+        self
+    }
+
     /// Opens the database in the multi-thread [threading mode][1].
     ///
     /// [1]: https://www.sqlite.org/threadsafe.html
@@ -244,6 +2694,22 @@ impl OpenFlags {
         self.0 |= ffi::SQLITE_OPEN_READWRITE;
         self
     }
+
+    /// Open the database in [shared-cache mode][1].
+    ///
+    /// [1]: https://www.sqlite.org/sharedcache.html
+    pub fn set_shared_cache(mut self) -> Self {
+        self.0 |= ffi::SQLITE_OPEN_SHAREDCACHE;
+        self
+    }
+
+    /// Interpret the path as a [URI filename][1].
+    ///
+    /// [1]: https://www.sqlite.org/uri.html
+    pub fn set_uri(mut self) -> Self {
+        self.0 |= ffi::SQLITE_OPEN_URI;
+        self
+    }
 }
 
 impl Default for OpenFlags {
@@ -253,6 +2719,17 @@ impl Default for OpenFlags {
     }
 }
 
+impl QueryPlanNode {
+    /// Iterate over the steps whose parent is the step.
+    pub fn children<'l>(
+        &self,
+        nodes: &'l [QueryPlanNode],
+    ) -> impl Iterator<Item = &'l QueryPlanNode> {
+        let id = self.id;
+        nodes.iter().filter(move |node| node.parent_id == id)
+    }
+}
+
 impl Deref for ConnectionWithFullMutex {
     type Target = Connection;
 
@@ -273,6 +2750,58 @@ unsafe impl Sync for ConnectionWithFullMutex {}
 
 unsafe impl Send for Raw {}
 
+pub fn statement_cache(connection: &Connection) -> MutexGuard<'_, StatementCache> {
+    connection
+        .statement_cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+// Unwinding into SQLite is undefined behavior, so a panic in a callback is
+// caught and replaced with a fallback value.
+pub fn guard<T, F>(fallback: T, function: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    panic::catch_unwind(AssertUnwindSafe(function)).unwrap_or(fallback)
+}
+
+// Percent-encode a string for the path of a URI filename.
+fn encode(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                result.push(byte as char)
+            }
+            _ => result.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    result
+}
+
+fn literal(value: &Value) -> Result<String> {
+    Ok(match value {
+        Value::Float(value) if !value.is_finite() => {
+            raise!("the value should be finite ({})", value)
+        }
+        Value::Float(value) => format!("{:?}", value),
+        Value::String(ref value) => format!("'{}'", value.replace('\'', "''")),
+        _ => value.to_string(),
+    })
+}
+
+fn batch_error(raw: *mut ffi::sqlite3, code: c_int, index: usize) -> ::Error {
+    let mut error = ::error::last(raw).unwrap_or(::Error {
+        code: Some(code as isize),
+        extended_code: None,
+        message: None,
+    });
+    let message = error.message.as_deref().unwrap_or("an SQLite error");
+    error.message = Some(format!("statement {index}: {message}"));
+    error
+}
+
 extern "C" fn busy_callback<F>(callback: *mut c_void, attempts: c_int) -> c_int
 where
     F: FnMut(usize) -> bool,
@@ -280,6 +2809,103 @@ where
     unsafe { c_int::from((*(callback as *mut F))(attempts as usize)) }
 }
 
+extern "C" fn collation_callback<F>(
+    callback: *mut c_void,
+    raw: *mut ffi::sqlite3,
+    _: c_int,
+    name: *const c_char,
+) where
+    F: FnMut(&CollationHandle<'_>, &str),
+{
+    unsafe {
+        if let Ok(name) = c_str_to_str!(name) {
+            let handle = ::function::collation_handle(raw);
+            guard((), || (*(callback as *mut F))(&handle, name));
+        }
+    }
+}
+
+extern "C" fn commit_callback<F>(callback: *mut c_void) -> c_int
+where
+    F: FnMut() -> bool,
+{
+    unsafe { guard(1, || c_int::from(!(*(callback as *mut F))())) }
+}
+
+extern "C" fn progress_callback<F>(callback: *mut c_void) -> c_int
+where
+    F: FnMut() -> bool,
+{
+    unsafe { guard(1, || c_int::from(!(*(callback as *mut F))())) }
+}
+
+extern "C" fn rollback_callback<F>(callback: *mut c_void)
+where
+    F: FnMut(),
+{
+    unsafe { guard((), || (*(callback as *mut F))()) }
+}
+
+extern "C" fn update_callback<F>(
+    callback: *mut c_void,
+    action: c_int,
+    database: *const c_char,
+    table: *const c_char,
+    rowid: ffi::sqlite3_int64,
+) where
+    F: FnMut(Action, &str, &str, i64),
+{
+    let action = match action {
+        ffi::SQLITE_INSERT => Action::Insert,
+        ffi::SQLITE_UPDATE => Action::Update,
+        ffi::SQLITE_DELETE => Action::Delete,
+        _ => return,
+    };
+    unsafe {
+        let database = c_str_to_str!(database).unwrap_or_default();
+        let table = c_str_to_str!(table).unwrap_or_default();
+        guard((), || {
+            (*(callback as *mut F))(action, database, table, rowid)
+        });
+    }
+}
+
+// SQLite invokes the callback with the mutex guarding the list of blocked
+// connections held, which is also held when a callback is canceled, so the
+// boxes are still alive here.
+extern "C" fn unlock_notify_callback(arguments: *mut *mut c_void, count: c_int) {
+    unsafe {
+        for index in 0..count.max(0) as usize {
+            let callback = &*(*arguments.add(index) as *const UnlockNotifyCallback);
+            let callback = callback
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take();
+            if let Some(callback) = callback {
+                guard((), callback);
+            }
+        }
+    }
+}
+
+extern "C" fn wal_callback<F>(
+    callback: *mut c_void,
+    _: *mut ffi::sqlite3,
+    database: *const c_char,
+    pages: c_int,
+) -> c_int
+where
+    F: FnMut(&str, usize),
+{
+    unsafe {
+        let database = c_str_to_str!(database).unwrap_or_default();
+        guard((), || {
+            (*(callback as *mut F))(database, pages.max(0) as usize)
+        });
+    }
+    ffi::SQLITE_OK
+}
+
 extern "C" fn process_callback<F>(
     callback: *mut c_void,
     count: c_int,
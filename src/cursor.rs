@@ -1,27 +1,40 @@
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::iter::FromIterator;
 use std::ops::{Deref, Index};
 use std::rc::Rc;
 
 use error::{Error, Result};
 use statement::{Bindable, State, Statement};
-use value::Value;
+use value::{FromSql, Value};
 
 /// An iterator for a prepared statement.
+///
+/// The cursor dereferences to the statement, which makes it possible to
+/// inspect the statement, for instance, via `column_names`, while iterating.
 pub struct Cursor<'l, 'm> {
     statement: &'m mut Statement<'l>,
     values: Vec<Value>,
 }
 
 /// An iterator for a prepared statement with ownership.
+///
+/// See `Cursor` for details.
 pub struct CursorWithOwnership<'l> {
     statement: Statement<'l>,
     values: Vec<Value>,
 }
 
+/// An iterator mapping the rows of a cursor.
+pub struct MappedCursor<T, F> {
+    cursor: T,
+    map: F,
+}
+
 /// A row.
 #[derive(Debug)]
 pub struct Row {
+    column_names: Rc<Vec<String>>,
     column_mapping: Rc<HashMap<String, usize>>,
     values: Vec<Value>,
 }
@@ -32,6 +45,15 @@ pub trait RowIndex: std::fmt::Debug {
     ///
     /// The first column has index 0.
     fn index(self, row: &Row) -> usize;
+
+    /// Identify the ordinal position, failing if the column does not exist.
+    #[inline]
+    fn try_index(self, row: &Row) -> Result<usize>
+    where
+        Self: Sized,
+    {
+        Ok(self.index(row))
+    }
 }
 
 macro_rules! implement(
@@ -39,8 +61,9 @@ macro_rules! implement(
         impl<$($lifetime),+> $type<$($lifetime),+> {
             /// Bind values to parameters.
             ///
-            /// In case of integer indices, the first parameter has index 1. See
-            /// `Statement::bind` for further details.
+            /// The statement is reset first, so the same cursor can be run again
+            /// with different values. In case of integer indices, the first
+            /// parameter has index 1. See `Statement::bind` for further details.
             pub fn bind<T: Bindable>(self, value: T) -> Result<Self> {
                 #[allow(unused_mut)]
                 let mut cursor = self.reset()?;
@@ -62,6 +85,47 @@ macro_rules! implement(
                 Ok(cursor)
             }
 
+            /// Return the only row, failing if there are no rows or more than one.
+            #[allow(unused_mut)]
+            pub fn exactly_one(mut self) -> Result<Row> {
+                let row = match self.next() {
+                    Some(row) => row?,
+                    _ => raise!("the statement returned no rows"),
+                };
+                if self.next().transpose()?.is_some() {
+                    raise!("the statement returned more than one row");
+                }
+                Ok(row)
+            }
+
+            /// Return the first row if any without reading the rest.
+            #[allow(unused_mut)]
+            pub fn first(mut self) -> Result<Option<Row>> {
+                self.next().transpose()
+            }
+
+            /// Map each row lazily.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// let connection = sqlite::open(":memory:")?;
+            /// let mut statement = connection.prepare("SELECT 1 UNION SELECT 2")?;
+            /// let values = statement
+            ///     .iter()
+            ///     .map_rows(|row| row.try_get::<i64, _>(0))
+            ///     .collect::<sqlite::Result<Vec<_>>>()?;
+            /// assert_eq!(values, vec![1, 2]);
+            /// # Ok::<(), sqlite::Error>(())
+            /// ```
+            #[inline]
+            pub fn map_rows<T, F>(self, map: F) -> MappedCursor<Self, F>
+            where
+                F: FnMut(Row) -> Result<T>,
+            {
+                MappedCursor { cursor: self, map }
+            }
+
             /// Reset the internal state.
             #[allow(unused_mut)]
             pub fn reset(mut self) -> Result<Self> {
@@ -69,6 +133,12 @@ macro_rules! implement(
                 Ok(self)
             }
 
+            /// Collect the rows, stopping at the first error.
+            #[inline]
+            pub fn try_collect<T: FromIterator<Row>>(self) -> Result<T> {
+                self.collect()
+            }
+
             /// Advance to the next row and read all columns.
             pub fn try_next(&mut self) -> Result<Option<&[Value]>> {
                 if self.statement.next()? == State::Done {
@@ -81,6 +151,15 @@ macro_rules! implement(
             }
         }
 
+        impl<$($lifetime),+> std::fmt::Debug for $type<$($lifetime),+> {
+            fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter
+                    .debug_struct(stringify!($type))
+                    .field("statement", &self.statement)
+                    .finish()
+            }
+        }
+
         impl<$($lifetime),+> Deref for $type<$($lifetime),+> {
             type Target = Statement<'l>;
 
@@ -94,10 +173,12 @@ macro_rules! implement(
             type Item = Result<Row>;
 
             fn next(&mut self) -> Option<Self::Item> {
+                let column_names = self.statement.column_names_shared();
                 let column_mapping = self.statement.column_mapping();
                 self.try_next()
                     .map(|row| {
                         row.map(|row| Row {
+                            column_names,
                             column_mapping,
                             values: row.to_vec(),
                         })
@@ -111,6 +192,16 @@ macro_rules! implement(
 implement!(Cursor<'l, 'm>);
 implement!(CursorWithOwnership<'l>);
 
+/// A type that can be constructed from a row.
+///
+/// With the `macros` feature enabled, the trait can be derived for structs
+/// with named fields. Each field is then read from the column with the same
+/// name unless overridden via `#[column = "name"]`.
+pub trait FromRow: Sized {
+    /// Construct an instance from a row.
+    fn from_row(row: &Row) -> Result<Self>;
+}
+
 impl<'l> From<CursorWithOwnership<'l>> for Statement<'l> {
     #[inline]
     fn from(cursor: CursorWithOwnership<'l>) -> Self {
@@ -119,9 +210,45 @@ impl<'l> From<CursorWithOwnership<'l>> for Statement<'l> {
 }
 
 impl Row {
+    /// Return the number of columns.
+    #[inline]
+    pub fn column_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Return the name of a column if exists.
+    ///
+    /// The first column has index 0.
+    #[inline]
+    pub fn column_name(&self, index: usize) -> Option<&str> {
+        self.column_names.get(index).map(String::as_str)
+    }
+
+    /// Return column names.
+    #[inline]
+    pub fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+
     /// Read the value in a column.
     ///
-    /// In case of integer indices, the first column has index 0.
+    /// In case of integer indices, the first column has index 0. Strings and
+    /// binary data can be read as `&str` and `&[u8]`, respectively, which
+    /// borrow from the row instead of allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT, email TEXT)")?;
+    /// connection.execute("INSERT INTO users VALUES ('Alice', NULL)")?;
+    /// let mut statement = connection.prepare("SELECT * FROM users")?;
+    /// let row = statement.iter().next().unwrap()?;
+    /// let name: &str = row.read("name");
+    /// let email: Option<&str> = row.read("email");
+    /// assert_eq!((name, email), ("Alice", None));
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
     ///
     /// # Panics
     ///
@@ -144,7 +271,53 @@ impl Row {
         T: TryFrom<&'l Value, Error = Error>,
         U: RowIndex,
     {
-        T::try_from(&self.values[column.index(self)])
+        T::try_from(&self.values[column.try_index(self)?])
+    }
+
+    /// Try to read the value in a column via `FromSql`.
+    ///
+    /// In case of integer indices, the first column has index 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{FromSql, Value};
+    ///
+    /// struct Name(String);
+    ///
+    /// impl FromSql for Name {
+    ///     fn from_sql(value: &Value) -> sqlite::Result<Self> {
+    ///         String::from_sql(value).map(Name)
+    ///     }
+    /// }
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// let mut statement = connection.prepare("SELECT 'Alice' AS name")?;
+    /// let row = statement.iter().next().unwrap()?;
+    /// let name: Name = row.try_get("name")?;
+    /// assert_eq!(name.0, "Alice");
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn try_get<T, U>(&self, column: U) -> Result<T>
+    where
+        T: FromSql,
+        U: RowIndex,
+    {
+        T::from_sql(&self.values[column.try_index(self)?])
+    }
+}
+
+impl<T, F, U> Iterator for MappedCursor<T, F>
+where
+    T: Iterator<Item = Result<Row>>,
+    F: FnMut(Row) -> Result<U>,
+{
+    type Item = Result<U>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next().map(|row| row.and_then(&mut self.map))
     }
 }
 
@@ -155,6 +328,35 @@ impl From<Row> for Vec<Value> {
     }
 }
 
+impl IntoIterator for Row {
+    type Item = (String, Value);
+    type IntoIter = std::iter::Zip<std::vec::IntoIter<String>, std::vec::IntoIter<Value>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        let column_names =
+            Rc::try_unwrap(self.column_names).unwrap_or_else(|names| (*names).clone());
+        column_names.into_iter().zip(self.values)
+    }
+}
+
+impl<'l> IntoIterator for &'l Row {
+    type Item = (&'l str, &'l Value);
+    type IntoIter = std::iter::Zip<
+        std::iter::Map<std::slice::Iter<'l, String>, fn(&String) -> &str>,
+        std::slice::Iter<'l, Value>,
+    >;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        let column_names = self
+            .column_names
+            .iter()
+            .map(String::as_str as fn(&String) -> &str);
+        column_names.zip(self.values.iter())
+    }
+}
+
 impl<T> Index<T> for Row
 where
     T: RowIndex,
@@ -166,6 +368,22 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Row {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.values.len()))?;
+        for (name, value) in self {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
 impl RowIndex for &str {
     #[inline]
     fn index(self, row: &Row) -> usize {
@@ -175,6 +393,42 @@ impl RowIndex for &str {
         );
         row.column_mapping[self]
     }
+
+    #[inline]
+    fn try_index(self, row: &Row) -> Result<usize> {
+        match row.column_mapping.get(self) {
+            Some(&index) => Ok(index),
+            _ => raise!("the row should have a column named {}", self),
+        }
+    }
+}
+
+impl RowIndex for String {
+    #[inline]
+    fn index(self, row: &Row) -> usize {
+        RowIndex::index(self.as_str(), row)
+    }
+
+    #[inline]
+    fn try_index(self, row: &Row) -> Result<usize> {
+        RowIndex::try_index(self.as_str(), row)
+    }
+}
+
+impl RowIndex for i32 {
+    #[inline]
+    fn index(self, row: &Row) -> usize {
+        debug_assert!(self >= 0, "the index is out of range");
+        RowIndex::index(self as usize, row)
+    }
+
+    #[inline]
+    fn try_index(self, row: &Row) -> Result<usize> {
+        if self < 0 {
+            raise!("the index is out of range ({})", self);
+        }
+        RowIndex::try_index(self as usize, row)
+    }
 }
 
 impl RowIndex for usize {
@@ -183,6 +437,14 @@ impl RowIndex for usize {
         debug_assert!(self < row.values.len(), "the index is out of range");
         self
     }
+
+    #[inline]
+    fn try_index(self, row: &Row) -> Result<usize> {
+        if self >= row.values.len() {
+            raise!("the index is out of range ({})", self);
+        }
+        Ok(self)
+    }
 }
 
 pub fn new<'l, 'm>(statement: &'m mut Statement<'l>) -> Cursor<'l, 'm> {
@@ -1,3 +1,4 @@
+use libc::c_int;
 use std::{error, fmt};
 
 /// An error.
@@ -5,6 +6,7 @@ use std::{error, fmt};
 pub struct Error {
     /// The error code.
     pub code: Option<isize>,
+    pub(crate) extended_code: Option<i32>,
     /// The error message.
     pub message: Option<String>,
 }
@@ -18,6 +20,7 @@ macro_rules! error(
             Some(error) => return Err(error),
             _ => return Err(::Error {
                 code: Some($code as isize),
+                extended_code: None,
                 message: None,
             }),
         }
@@ -36,6 +39,7 @@ macro_rules! ok(
             ::ffi::SQLITE_OK => {}
             code => return Err(::Error {
                 code: Some(code as isize),
+                extended_code: None,
                 message: None,
             }),
         }
@@ -46,18 +50,90 @@ macro_rules! raise(
     ($message:expr $(, $($token:tt)* )?) => (
         return Err(::Error {
             code: None,
+            extended_code: None,
             message: Some(format!($message $(, $($token)* )*)),
         })
     );
 );
 
+impl Error {
+    /// Create an error given a primary or extended [result code][1].
+    ///
+    /// The message is the English description of the code given by SQLite.
+    ///
+    /// [1]: https://www.sqlite.org/rescode.html
+    pub fn from_code(code: i32) -> Error {
+        let message = unsafe {
+            let pointer = ffi::sqlite3_errstr(code);
+            if pointer.is_null() {
+                None
+            } else {
+                Some(c_str_to_string!(pointer))
+            }
+        };
+        Error {
+            code: Some((code & 0xff) as isize),
+            extended_code: if code > 0xff { Some(code) } else { None },
+            message,
+        }
+    }
+
+    /// Return the [extended result code][1] if it differs from the primary one.
+    ///
+    /// [1]: https://www.sqlite.org/rescode.html#extrc
+    #[inline]
+    pub fn extended_code(&self) -> Option<i32> {
+        self.extended_code
+    }
+
+    /// Return the name of the primary result code such as `SQLITE_BUSY`.
+    ///
+    /// Extended result codes are named after their primary result codes.
+    pub fn code_name(&self) -> Option<&'static str> {
+        Some(match self.code? as c_int & 0xff {
+            ffi::SQLITE_OK => "SQLITE_OK",
+            ffi::SQLITE_ERROR => "SQLITE_ERROR",
+            ffi::SQLITE_INTERNAL => "SQLITE_INTERNAL",
+            ffi::SQLITE_PERM => "SQLITE_PERM",
+            ffi::SQLITE_ABORT => "SQLITE_ABORT",
+            ffi::SQLITE_BUSY => "SQLITE_BUSY",
+            ffi::SQLITE_LOCKED => "SQLITE_LOCKED",
+            ffi::SQLITE_NOMEM => "SQLITE_NOMEM",
+            ffi::SQLITE_READONLY => "SQLITE_READONLY",
+            ffi::SQLITE_INTERRUPT => "SQLITE_INTERRUPT",
+            ffi::SQLITE_IOERR => "SQLITE_IOERR",
+            ffi::SQLITE_CORRUPT => "SQLITE_CORRUPT",
+            ffi::SQLITE_NOTFOUND => "SQLITE_NOTFOUND",
+            ffi::SQLITE_FULL => "SQLITE_FULL",
+            ffi::SQLITE_CANTOPEN => "SQLITE_CANTOPEN",
+            ffi::SQLITE_PROTOCOL => "SQLITE_PROTOCOL",
+            ffi::SQLITE_EMPTY => "SQLITE_EMPTY",
+            ffi::SQLITE_SCHEMA => "SQLITE_SCHEMA",
+            ffi::SQLITE_TOOBIG => "SQLITE_TOOBIG",
+            ffi::SQLITE_CONSTRAINT => "SQLITE_CONSTRAINT",
+            ffi::SQLITE_MISMATCH => "SQLITE_MISMATCH",
+            ffi::SQLITE_MISUSE => "SQLITE_MISUSE",
+            ffi::SQLITE_NOLFS => "SQLITE_NOLFS",
+            ffi::SQLITE_AUTH => "SQLITE_AUTH",
+            ffi::SQLITE_FORMAT => "SQLITE_FORMAT",
+            ffi::SQLITE_RANGE => "SQLITE_RANGE",
+            ffi::SQLITE_NOTADB => "SQLITE_NOTADB",
+            ffi::SQLITE_NOTICE => "SQLITE_NOTICE",
+            ffi::SQLITE_WARNING => "SQLITE_WARNING",
+            ffi::SQLITE_ROW => "SQLITE_ROW",
+            ffi::SQLITE_DONE => "SQLITE_DONE",
+            _ => return None,
+        })
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        match (self.code, &self.message) {
-            (Some(code), Some(message)) => write!(formatter, "{message} (code {code})"),
-            (Some(code), _) => write!(formatter, "an SQLite error (code {code})"),
-            (_, Some(message)) => message.fmt(formatter),
-            _ => write!(formatter, "an SQLite error"),
+        let message = self.message.as_deref().unwrap_or("an SQLite error");
+        match (self.code, self.code_name()) {
+            (Some(code), Some(name)) => write!(formatter, "{message} (code {code}, {name})"),
+            (Some(code), _) => write!(formatter, "{message} (code {code})"),
+            _ => message.fmt(formatter),
         }
     }
 }
@@ -81,8 +157,14 @@ pub fn last(raw: *mut ffi::sqlite3) -> Option<Error> {
         if message.is_null() {
             return None;
         }
+        let extended_code = ffi::sqlite3_extended_errcode(raw);
         Some(Error {
             code: Some(code as isize),
+            extended_code: if extended_code > 0xff {
+                Some(extended_code)
+            } else {
+                None
+            },
             message: Some(c_str_to_string!(message)),
         })
     }
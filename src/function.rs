@@ -0,0 +1,615 @@
+use ffi;
+use libc::{c_char, c_int, c_void};
+#[cfg(feature = "pointer")]
+use std::any::TypeId;
+use std::cmp::Ordering;
+#[cfg(feature = "pointer")]
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "pointer")]
+use std::sync::{Mutex, PoisonError};
+
+use error::Result;
+use value::Value;
+
+// https://www.sqlite.org/c3ref/c_deterministic.html
+const SQLITE_INNOCUOUS: c_int = 0x000200000;
+
+#[cfg(feature = "pointer")]
+extern "C" {
+    // https://www.sqlite.org/c3ref/bind_blob.html
+    fn sqlite3_bind_pointer(
+        statement: *mut ffi::sqlite3_stmt,
+        index: c_int,
+        pointer: *mut c_void,
+        type_name: *const c_char,
+        destroy: Option<extern "C" fn(*mut c_void)>,
+    ) -> c_int;
+
+    // https://www.sqlite.org/c3ref/value_blob.html
+    fn sqlite3_value_pointer(
+        value: *mut ffi::sqlite3_value,
+        type_name: *const c_char,
+    ) -> *mut c_void;
+}
+
+#[cfg(feature = "window")]
+extern "C" {
+    // https://www.sqlite.org/c3ref/create_function.html
+    fn sqlite3_create_window_function(
+        db: *mut ffi::sqlite3,
+        name: *const c_char,
+        argument_count: c_int,
+        text_representation: c_int,
+        application: *mut c_void,
+        step: Option<
+            unsafe extern "C" fn(*mut ffi::sqlite3_context, c_int, *mut *mut ffi::sqlite3_value),
+        >,
+        final_: Option<unsafe extern "C" fn(*mut ffi::sqlite3_context)>,
+        value: Option<unsafe extern "C" fn(*mut ffi::sqlite3_context)>,
+        inverse: Option<
+            unsafe extern "C" fn(*mut ffi::sqlite3_context, c_int, *mut *mut ffi::sqlite3_value),
+        >,
+        destroy: Option<unsafe extern "C" fn(*mut c_void)>,
+    ) -> c_int;
+}
+
+/// A handle for creating collations from within the callback given to
+/// `Connection::collation_needed`.
+pub struct CollationHandle<'l> {
+    raw: *mut ffi::sqlite3,
+    phantom: PhantomData<&'l ()>,
+}
+
+/// Flags for creating a function.
+#[derive(Clone, Copy, Debug)]
+pub struct FunctionFlags(c_int);
+
+/// The context of a call to a scalar function.
+pub struct FunctionContext<'l> {
+    #[cfg_attr(not(feature = "pointer"), allow(dead_code))]
+    raw: &'l [*mut ffi::sqlite3_value],
+    values: Vec<Value>,
+}
+
+// The type identifier comes first in order to be checked before the value is
+// accessed.
+#[cfg(feature = "pointer")]
+#[repr(C)]
+struct Pointer<T> {
+    type_id: TypeId,
+    value: Box<T>,
+}
+
+/// An aggregate function.
+///
+/// A new instance is created via `Default` for each group of rows.
+pub trait Aggregate: Default {
+    /// Process a row.
+    fn step(&mut self, values: &[Value]);
+
+    /// Compute the result.
+    fn finalize(self) -> Value;
+}
+
+/// An aggregate window function.
+///
+/// In addition to adding rows via `step`, rows leaving the window frame are
+/// removed via `inverse`, and the result for the current frame is computed via
+/// `value` without consuming the state.
+#[cfg(feature = "window")]
+pub trait WindowAggregate: Aggregate {
+    /// Remove a row from the window frame.
+    fn inverse(&mut self, values: &[Value]);
+
+    /// Compute the result for the current window frame.
+    fn value(&self) -> Value;
+}
+
+impl<'l> CollationHandle<'l> {
+    /// Create a collation for ordering text.
+    ///
+    /// See `Connection::create_collation` for details.
+    #[inline]
+    pub fn create_collation<F>(&self, name: &str, function: F) -> Result<()>
+    where
+        F: Fn(&str, &str) -> Ordering + Send + 'static,
+    {
+        create_collation(self.raw, name, function)
+    }
+
+    /// Remove a collation.
+    #[inline]
+    pub fn remove_collation(&self, name: &str) -> Result<()> {
+        remove_collation(self.raw, name)
+    }
+}
+
+impl FunctionFlags {
+    /// Create flags for creating a function.
+    #[inline]
+    pub fn new() -> Self {
+        FunctionFlags(0)
+    }
+
+    /// Mark the function as always returning the same result given the same
+    /// arguments, which enables additional query optimizations.
+    pub fn set_deterministic(mut self) -> Self {
+        self.0 |= ffi::SQLITE_DETERMINISTIC;
+        self
+    }
+
+    /// Mark the function as having no side effects and being safe to use in
+    /// schema structures such as views and triggers.
+    pub fn set_innocuous(mut self) -> Self {
+        self.0 |= SQLITE_INNOCUOUS;
+        self
+    }
+}
+
+impl<'l> FunctionContext<'l> {
+    /// Return the arguments.
+    #[inline]
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Return a value passed as an argument via `Statement::bind_pointer`.
+    ///
+    /// In case of integer indices, the first argument has index 0. `None` is
+    /// returned if the argument is not a pointer bound with the same type name
+    /// and the same Rust type.
+    #[cfg(feature = "pointer")]
+    pub fn get_pointer<T: 'static>(&self, index: usize, type_name: &str) -> Option<&T> {
+        let raw = *self.raw.get(index)?;
+        let type_name = CString::new(type_name).ok()?;
+        unsafe {
+            let pointer = sqlite3_value_pointer(raw, type_name.as_ptr()) as *const Pointer<T>;
+            if pointer.is_null() || *(pointer as *const TypeId) != TypeId::of::<T>() {
+                return None;
+            }
+            Some(&*(*pointer).value)
+        }
+    }
+}
+
+impl Default for FunctionFlags {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn create_scalar<F>(
+    raw: *mut ffi::sqlite3,
+    name: &str,
+    argument_count: i32,
+    flags: FunctionFlags,
+    function: F,
+) -> Result<()>
+where
+    F: Fn(&[Value]) -> Value + Send + 'static,
+{
+    unsafe {
+        let name = str_to_cstr!(name);
+        let function = Box::into_raw(Box::new(function));
+        ok!(
+            raw,
+            ffi::sqlite3_create_function_v2(
+                raw,
+                name.as_ptr(),
+                argument_count as c_int,
+                ffi::SQLITE_UTF8 | flags.0,
+                function as *mut _,
+                Some(scalar_callback::<F>),
+                None,
+                None,
+                Some(destroy_callback::<F>),
+            )
+        );
+    }
+    Ok(())
+}
+
+pub fn create_scalar_with_context<F>(
+    raw: *mut ffi::sqlite3,
+    name: &str,
+    argument_count: i32,
+    flags: FunctionFlags,
+    function: F,
+) -> Result<()>
+where
+    F: Fn(&FunctionContext<'_>) -> Value + Send + 'static,
+{
+    unsafe {
+        let name = str_to_cstr!(name);
+        let function = Box::into_raw(Box::new(function));
+        ok!(
+            raw,
+            ffi::sqlite3_create_function_v2(
+                raw,
+                name.as_ptr(),
+                argument_count as c_int,
+                ffi::SQLITE_UTF8 | flags.0,
+                function as *mut _,
+                Some(scalar_context_callback::<F>),
+                None,
+                None,
+                Some(destroy_callback::<F>),
+            )
+        );
+    }
+    Ok(())
+}
+
+pub fn create_aggregate<A>(raw: *mut ffi::sqlite3, name: &str, argument_count: i32) -> Result<()>
+where
+    A: Aggregate,
+{
+    unsafe {
+        ok!(
+            raw,
+            ffi::sqlite3_create_function_v2(
+                raw,
+                str_to_cstr!(name).as_ptr(),
+                argument_count as c_int,
+                ffi::SQLITE_UTF8,
+                std::ptr::null_mut(),
+                None,
+                Some(step_callback::<A>),
+                Some(final_callback::<A>),
+                None,
+            )
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "window")]
+pub fn create_window<A>(raw: *mut ffi::sqlite3, name: &str, argument_count: i32) -> Result<()>
+where
+    A: WindowAggregate,
+{
+    if ::version() < 3025000 {
+        raise!("window functions require SQLite 3.25 or newer");
+    }
+    unsafe {
+        ok!(
+            raw,
+            sqlite3_create_window_function(
+                raw,
+                str_to_cstr!(name).as_ptr(),
+                argument_count as c_int,
+                ffi::SQLITE_UTF8,
+                std::ptr::null_mut(),
+                Some(step_callback::<A>),
+                Some(final_callback::<A>),
+                Some(value_callback::<A>),
+                Some(inverse_callback::<A>),
+                None,
+            )
+        );
+    }
+    Ok(())
+}
+
+pub fn remove(raw: *mut ffi::sqlite3, name: &str, argument_count: i32) -> Result<()> {
+    unsafe {
+        ok!(
+            raw,
+            ffi::sqlite3_create_function_v2(
+                raw,
+                str_to_cstr!(name).as_ptr(),
+                argument_count as c_int,
+                ffi::SQLITE_UTF8,
+                std::ptr::null_mut(),
+                None,
+                None,
+                None,
+                None,
+            )
+        );
+    }
+    Ok(())
+}
+
+#[inline]
+pub fn collation_handle<'l>(raw: *mut ffi::sqlite3) -> CollationHandle<'l> {
+    CollationHandle {
+        raw,
+        phantom: PhantomData,
+    }
+}
+
+pub fn create_collation<F>(raw: *mut ffi::sqlite3, name: &str, function: F) -> Result<()>
+where
+    F: Fn(&str, &str) -> Ordering + Send + 'static,
+{
+    unsafe {
+        let name = str_to_cstr!(name);
+        let function = Box::into_raw(Box::new(function));
+        ok!(
+            raw,
+            ffi::sqlite3_create_collation_v2(
+                raw,
+                name.as_ptr(),
+                ffi::SQLITE_UTF8,
+                function as *mut _,
+                Some(collation_callback::<F>),
+                Some(destroy_callback::<F>),
+            )
+        );
+    }
+    Ok(())
+}
+
+pub fn remove_collation(raw: *mut ffi::sqlite3, name: &str) -> Result<()> {
+    unsafe {
+        ok!(
+            raw,
+            ffi::sqlite3_create_collation_v2(
+                raw,
+                str_to_cstr!(name).as_ptr(),
+                ffi::SQLITE_UTF8,
+                std::ptr::null_mut(),
+                None,
+                None,
+            )
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "pointer")]
+pub fn bind_pointer<T: 'static>(
+    raw: (*mut ffi::sqlite3_stmt, *mut ffi::sqlite3),
+    index: usize,
+    value: Box<T>,
+    type_name: &'static str,
+) -> Result<()> {
+    let type_name = intern(type_name)?;
+    let pointer = Box::into_raw(Box::new(Pointer {
+        type_id: TypeId::of::<T>(),
+        value,
+    }));
+    unsafe {
+        ok!(
+            raw.1,
+            sqlite3_bind_pointer(
+                raw.0,
+                index as c_int,
+                pointer as *mut _,
+                type_name.as_ptr(),
+                Some(destroy_callback::<Pointer<T>>),
+            )
+        );
+    }
+    Ok(())
+}
+
+// SQLite keeps the type name for as long as the pointer is bound, and the name
+// is therefore kept alive for the lifetime of the process.
+#[cfg(feature = "pointer")]
+fn intern(name: &'static str) -> Result<&'static CStr> {
+    static NAMES: Mutex<Vec<(&str, &CStr)>> = Mutex::new(Vec::new());
+    let mut names = NAMES.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(&(_, value)) = names.iter().find(|(key, _)| *key == name) {
+        return Ok(value);
+    }
+    let value: &'static CStr = Box::leak(str_to_cstr!(name).into_boxed_c_str());
+    names.push((name, value));
+    Ok(value)
+}
+
+unsafe fn read_key<'l>(pointer: *const c_void, count: c_int) -> &'l [u8] {
+    if pointer.is_null() || count <= 0 {
+        return &[];
+    }
+    std::slice::from_raw_parts(pointer as *const u8, count as usize)
+}
+
+unsafe fn read_values(count: c_int, values: *mut *mut ffi::sqlite3_value) -> Vec<Value> {
+    (0..(count as isize))
+        .map(|index| read_value(*values.offset(index)))
+        .collect()
+}
+
+unsafe fn read_value(raw: *mut ffi::sqlite3_value) -> Value {
+    match ffi::sqlite3_value_type(raw) {
+        ffi::SQLITE_BLOB => {
+            let pointer = ffi::sqlite3_value_blob(raw);
+            if pointer.is_null() {
+                return Value::Binary(vec![]);
+            }
+            let count = ffi::sqlite3_value_bytes(raw) as usize;
+            Value::Binary(std::slice::from_raw_parts(pointer as *const u8, count).to_vec())
+        }
+        ffi::SQLITE_FLOAT => Value::Float(ffi::sqlite3_value_double(raw)),
+        ffi::SQLITE_INTEGER => Value::Integer(ffi::sqlite3_value_int64(raw)),
+        ffi::SQLITE_TEXT => {
+            let pointer = ffi::sqlite3_value_text(raw);
+            if pointer.is_null() {
+                return Value::String(String::new());
+            }
+            let count = ffi::sqlite3_value_bytes(raw) as usize;
+            let bytes = std::slice::from_raw_parts(pointer, count);
+            Value::String(String::from_utf8_lossy(bytes).into_owned())
+        }
+        _ => Value::Null,
+    }
+}
+
+unsafe fn write_value(context: *mut ffi::sqlite3_context, value: &Value) {
+    match value {
+        Value::Binary(ref value) => ffi::sqlite3_result_blob(
+            context,
+            value.as_ptr() as *const _,
+            value.len() as c_int,
+            transient!(),
+        ),
+        Value::Float(value) => ffi::sqlite3_result_double(context, *value),
+        Value::Integer(value) => ffi::sqlite3_result_int64(context, *value),
+        Value::String(ref value) => ffi::sqlite3_result_text(
+            context,
+            value.as_ptr() as *const _,
+            value.len() as c_int,
+            transient!(),
+        ),
+        Value::Null => ffi::sqlite3_result_null(context),
+    }
+}
+
+extern "C" fn scalar_callback<F>(
+    context: *mut ffi::sqlite3_context,
+    count: c_int,
+    values: *mut *mut ffi::sqlite3_value,
+) where
+    F: Fn(&[Value]) -> Value,
+{
+    unsafe {
+        let function = ffi::sqlite3_user_data(context) as *const F;
+        let values = read_values(count, values);
+        protect(context, || write_value(context, &(*function)(&values)));
+    }
+}
+
+extern "C" fn scalar_context_callback<F>(
+    context: *mut ffi::sqlite3_context,
+    count: c_int,
+    values: *mut *mut ffi::sqlite3_value,
+) where
+    F: Fn(&FunctionContext<'_>) -> Value,
+{
+    unsafe {
+        let function = ffi::sqlite3_user_data(context) as *const F;
+        let raw = if count > 0 {
+            std::slice::from_raw_parts(values as *const _, count as usize)
+        } else {
+            &[]
+        };
+        let values = read_values(count, values);
+        protect(context, || {
+            write_value(context, &(*function)(&FunctionContext { raw, values }))
+        });
+    }
+}
+
+extern "C" fn collation_callback<F>(
+    function: *mut c_void,
+    first_count: c_int,
+    first: *const c_void,
+    second_count: c_int,
+    second: *const c_void,
+) -> c_int
+where
+    F: Fn(&str, &str) -> Ordering,
+{
+    unsafe {
+        let first = read_key(first, first_count);
+        let second = read_key(second, second_count);
+        ::connection::guard(0, || {
+            let ordering = (*(function as *const F))(
+                &String::from_utf8_lossy(first),
+                &String::from_utf8_lossy(second),
+            );
+            ordering as c_int
+        })
+    }
+}
+
+extern "C" fn step_callback<A>(
+    context: *mut ffi::sqlite3_context,
+    count: c_int,
+    values: *mut *mut ffi::sqlite3_value,
+) where
+    A: Aggregate,
+{
+    unsafe {
+        if let Some(aggregate) = aggregate::<A>(context) {
+            protect(context, || (*aggregate).step(&read_values(count, values)));
+        }
+    }
+}
+
+extern "C" fn final_callback<A>(context: *mut ffi::sqlite3_context)
+where
+    A: Aggregate,
+{
+    unsafe {
+        let pointer = ffi::sqlite3_aggregate_context(context, 0) as *mut *mut A;
+        let aggregate = if pointer.is_null() || (*pointer).is_null() {
+            A::default()
+        } else {
+            let aggregate = Box::from_raw(*pointer);
+            *pointer = std::ptr::null_mut();
+            *aggregate
+        };
+        protect(context, || write_value(context, &aggregate.finalize()));
+    }
+}
+
+#[cfg(feature = "window")]
+extern "C" fn inverse_callback<A>(
+    context: *mut ffi::sqlite3_context,
+    count: c_int,
+    values: *mut *mut ffi::sqlite3_value,
+) where
+    A: WindowAggregate,
+{
+    unsafe {
+        if let Some(aggregate) = aggregate::<A>(context) {
+            protect(context, || {
+                (*aggregate).inverse(&read_values(count, values))
+            });
+        }
+    }
+}
+
+#[cfg(feature = "window")]
+extern "C" fn value_callback<A>(context: *mut ffi::sqlite3_context)
+where
+    A: WindowAggregate,
+{
+    unsafe {
+        if let Some(aggregate) = aggregate::<A>(context) {
+            protect(context, || write_value(context, &(*aggregate).value()));
+        }
+    }
+}
+
+extern "C" fn destroy_callback<F>(function: *mut c_void) {
+    unsafe { drop(Box::from_raw(function as *mut F)) };
+}
+
+// Unwinding into SQLite is undefined behavior, so a panic is reported as an
+// error of the function instead.
+unsafe fn protect<F>(context: *mut ffi::sqlite3_context, function: F)
+where
+    F: FnOnce(),
+{
+    if panic::catch_unwind(AssertUnwindSafe(function)).is_err() {
+        let message = "the function panicked";
+        ffi::sqlite3_result_error(
+            context,
+            message.as_ptr() as *const c_char,
+            message.len() as c_int,
+        );
+    }
+}
+
+unsafe fn aggregate<A>(context: *mut ffi::sqlite3_context) -> Option<*mut A>
+where
+    A: Aggregate,
+{
+    let pointer = ffi::sqlite3_aggregate_context(context, std::mem::size_of::<*mut A>() as c_int)
+        as *mut *mut A;
+    if pointer.is_null() {
+        ffi::sqlite3_result_error_nomem(context);
+        return None;
+    }
+    if (*pointer).is_null() {
+        *pointer = Box::into_raw(Box::<A>::default());
+    }
+    Some(*pointer)
+}
@@ -87,10 +87,23 @@
 //! }
 //! ```
 //!
+//! ## Snapshots
+//!
+//! The `snapshot` feature enables `Connection::snapshot_get` and the related
+//! functions. They require SQLite 3.22 or newer compiled with
+//! `SQLITE_ENABLE_SNAPSHOT`, which most system libraries are not; otherwise,
+//! they return an error.
+//!
 //! [1]: https://www.sqlite.org
 
 extern crate libc;
+#[cfg(feature = "serde")]
+extern crate serde;
 extern crate sqlite3_sys as ffi;
+#[cfg(feature = "macros")]
+extern crate sqlite_macros;
+#[cfg(feature = "tokio")]
+extern crate tokio;
 
 macro_rules! c_str_to_str(
     ($string:expr) => (::std::str::from_utf8(::std::ffi::CStr::from_ptr($string).to_bytes()));
@@ -126,22 +139,72 @@ macro_rules! str_to_cstr(
     );
 );
 
+// https://sqlite.org/c3ref/c_static.html
+macro_rules! transient(
+    () => (::std::mem::transmute::<*const ::libc::c_void, Option<extern "C" fn(*mut ::libc::c_void)>>(!0 as *const ::libc::c_void));
+);
+
 #[macro_use]
 mod error;
 mod value;
 
+#[cfg(feature = "tokio")]
+mod asynchronous;
+mod authorizer;
+mod backup;
+mod blob;
+mod cache;
 mod connection;
 mod cursor;
+mod function;
+mod log;
+mod pool;
+#[cfg(feature = "serde")]
+mod serializer;
+#[cfg(feature = "snapshot")]
+mod snapshot;
 mod statement;
+mod trace;
+mod transaction;
+mod vfs;
 
 pub use error::{Error, Result};
-pub use value::{Type, Value};
+pub use value::{Blob, FromSql, SqlEqual, ToSql, Type, Value};
 
-pub use connection::{Connection, ConnectionWithFullMutex, OpenFlags};
-pub use cursor::{Cursor, CursorWithOwnership, Row, RowIndex};
+pub use authorizer::{Authorization, AuthorizerAction};
+pub use backup::{BackupHandle, BackupStatus};
+pub use blob::BlobHandle;
+pub use cache::CachedStatement;
+pub use connection::{
+    Action, AttachedDatabase, ColumnMetadata, Connection, ConnectionBuilder,
+    ConnectionWithFullMutex, DbStatus, ForeignKeyViolation, JournalMode, Limit, OpenFlags,
+    QueryPlanNode, StatementInfo, Synchronous, WalCheckpointMode,
+};
+pub use cursor::{Cursor, CursorWithOwnership, FromRow, MappedCursor, Row, RowIndex};
+#[cfg(feature = "window")]
+pub use function::WindowAggregate;
+pub use function::{Aggregate, CollationHandle, FunctionContext, FunctionFlags};
+pub use log::{remove_log_handler, set_log_handler};
+pub use pool::{ConnectionPool, ConnectionPoolBuilder, PooledConnection};
 pub use statement::{
     Bindable, BindableWithIndex, ColumnIndex, ParameterIndex, ReadableWithIndex, State, Statement,
+    StmtStatus,
 };
+pub use trace::{TraceEvent, TraceFlags};
+pub use transaction::{Savepoint, Transaction};
+pub use vfs::{get_default_vfs, set_default_vfs};
+
+#[cfg(feature = "tokio")]
+pub use asynchronous::{AsyncConnection, Task};
+#[cfg(feature = "vtab")]
+pub use connection::ConflictMode;
+#[cfg(feature = "snapshot")]
+pub use snapshot::Snapshot;
+#[cfg(feature = "isexplain")]
+pub use statement::ExplainMode;
+
+#[cfg(feature = "macros")]
+pub use sqlite_macros::FromRow;
 
 /// Open a read-write connection to a new or existing database.
 #[inline]
@@ -156,3 +219,39 @@ pub fn open<T: AsRef<std::path::Path>>(path: T) -> Result<Connection> {
 pub fn version() -> usize {
     unsafe { ffi::sqlite3_libversion_number() as usize }
 }
+
+/// Check if SQLite has been compiled with an option.
+///
+/// The `SQLITE_` prefix of the option is optional.
+///
+/// # Examples
+///
+/// ```
+/// if sqlite::compile_option_used("ENABLE_FTS5") {
+///     let connection = sqlite::open(":memory:")?;
+///     connection.execute("CREATE VIRTUAL TABLE documents USING fts5(body)")?;
+/// }
+/// # Ok::<(), sqlite::Error>(())
+/// ```
+#[inline]
+pub fn compile_option_used(option: &str) -> bool {
+    match std::ffi::CString::new(option) {
+        Ok(option) => unsafe { ffi::sqlite3_compileoption_used(option.as_ptr()) != 0 },
+        _ => false,
+    }
+}
+
+/// Return an option SQLite has been compiled with.
+///
+/// The options are indexed from 0, and `None` is returned for indices past the
+/// last one. The `SQLITE_` prefix is omitted.
+#[inline]
+pub fn compile_option(index: usize) -> Option<&'static str> {
+    unsafe {
+        let pointer = ffi::sqlite3_compileoption_get(index as libc::c_int);
+        if pointer.is_null() {
+            return None;
+        }
+        c_str_to_str!(pointer).ok()
+    }
+}
@@ -0,0 +1,80 @@
+use ffi;
+use libc::{c_char, c_int, c_void};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use error::{Error, Result};
+
+type LogCallback = dyn Fn(i32, &str) + Send + Sync;
+
+static HANDLER: Mutex<Option<Arc<LogCallback>>> = Mutex::new(None);
+
+/// Set a handler for the [error log][1] of SQLite.
+///
+/// The handler receives the result code and the message of each logged event.
+/// It should be quick and must not call back into SQLite.
+///
+/// Note that this is a process-global setting, which affects all databases in
+/// the process, including those opened outside of this crate. A previously set
+/// handler is replaced. SQLite 3.42 or newer is required.
+///
+/// # Examples
+///
+/// ```no_run
+/// sqlite::set_log_handler(|code, message| eprintln!("{message} (code {code})"))?;
+/// # sqlite::remove_log_handler()?;
+/// # Ok::<(), sqlite::Error>(())
+/// ```
+///
+/// [1]: https://www.sqlite.org/errlog.html
+pub fn set_log_handler<F>(callback: F) -> Result<()>
+where
+    F: Fn(i32, &str) + Send + Sync + 'static,
+{
+    unsafe {
+        configure(
+            log_callback as extern "C" fn(*mut c_void, c_int, *const c_char) as *const c_void,
+        )?;
+    }
+    *HANDLER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(callback));
+    Ok(())
+}
+
+/// Remove the handler for the error log of SQLite.
+///
+/// See `set_log_handler` for details.
+pub fn remove_log_handler() -> Result<()> {
+    unsafe { configure(std::ptr::null())? };
+    *HANDLER.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    Ok(())
+}
+
+// The handler can be changed after the library has been initialized only as of
+// SQLite 3.42, and the lock is not held here since a misuse is itself logged.
+unsafe fn configure(callback: *const c_void) -> Result<()> {
+    if ::version() < 3042000 {
+        raise!("setting the log handler requires SQLite 3.42 or newer");
+    }
+    match ffi::sqlite3_config(
+        ffi::SQLITE_CONFIG_LOG,
+        callback,
+        std::ptr::null_mut::<c_void>(),
+    ) {
+        ffi::SQLITE_OK => Ok(()),
+        code => Err(Error::from_code(code)),
+    }
+}
+
+extern "C" fn log_callback(_: *mut c_void, code: c_int, message: *const c_char) {
+    let handler = HANDLER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if let Some(handler) = handler {
+        let message = if message.is_null() {
+            String::new()
+        } else {
+            unsafe { c_str_to_string!(message) }
+        };
+        ::connection::guard((), || handler(code, &message));
+    }
+}
@@ -0,0 +1,214 @@
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+use connection::Connection;
+use error::Result;
+
+const MAX_SIZE: usize = 10;
+
+/// A pool of connections to a database.
+///
+/// The pool is cheap to clone, with the clones sharing the connections. Note
+/// that each connection to `:memory:` refers to a separate database; a shared
+/// in-memory database requires a URI filename with shared cache.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sqlite::ConnectionPool;
+///
+/// let pool = ConnectionPool::builder("database.sqlite3")
+///     .max_size(16)
+///     .busy_timeout(500)
+///     .build()?;
+/// let connection = pool.get()?;
+/// connection.execute("CREATE TABLE users (name TEXT)")?;
+/// # Ok::<(), sqlite::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct ConnectionPool {
+    inner: Arc<Inner>,
+}
+
+/// A builder of a pool of connections.
+#[derive(Clone, Debug)]
+pub struct ConnectionPoolBuilder {
+    path: PathBuf,
+    max_size: usize,
+    busy_timeout: Option<usize>,
+}
+
+/// A connection taken from a pool.
+///
+/// The connection is returned to the pool when dropped unless a transaction is
+/// still in progress, in which case the connection is closed instead.
+pub struct PooledConnection {
+    pool: Arc<Inner>,
+    connection: Option<Connection>,
+}
+
+struct Inner {
+    path: PathBuf,
+    max_size: usize,
+    busy_timeout: Option<usize>,
+    state: Mutex<State>,
+    available: Condvar,
+}
+
+struct State {
+    idle: Vec<Connection>,
+    size: usize,
+}
+
+impl ConnectionPool {
+    /// Create a builder of a pool of read-write connections to a new or
+    /// existing database.
+    #[inline]
+    pub fn builder<T: AsRef<Path>>(path: T) -> ConnectionPoolBuilder {
+        ConnectionPoolBuilder {
+            path: path.as_ref().to_path_buf(),
+            max_size: MAX_SIZE,
+            busy_timeout: None,
+        }
+    }
+
+    /// Take a connection from the pool.
+    ///
+    /// If all connections are in use and the pool has reached its maximum
+    /// size, the call blocks until a connection is returned.
+    pub fn get(&self) -> Result<PooledConnection> {
+        let mut state = lock(&self.inner.state);
+        loop {
+            if let Some(connection) = state.idle.pop() {
+                return Ok(PooledConnection {
+                    pool: self.inner.clone(),
+                    connection: Some(connection),
+                });
+            }
+            if state.size < self.inner.max_size {
+                state.size += 1;
+                drop(state);
+                return match self.inner.open() {
+                    Ok(connection) => Ok(PooledConnection {
+                        pool: self.inner.clone(),
+                        connection: Some(connection),
+                    }),
+                    Err(error) => {
+                        lock(&self.inner.state).size -= 1;
+                        self.inner.available.notify_one();
+                        Err(error)
+                    }
+                };
+            }
+            state = self
+                .inner
+                .available
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Return the number of idle connections.
+    #[inline]
+    pub fn idle_count(&self) -> usize {
+        lock(&self.inner.state).idle.len()
+    }
+
+    /// Return the number of open connections, both idle and in use.
+    #[inline]
+    pub fn size(&self) -> usize {
+        lock(&self.inner.state).size
+    }
+}
+
+impl ConnectionPoolBuilder {
+    /// Set the maximum number of connections, which is 10 by default.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Set the busy timeout of each connection in milliseconds.
+    ///
+    /// See `Connection::set_busy_timeout` for details.
+    pub fn busy_timeout(mut self, milliseconds: usize) -> Self {
+        self.busy_timeout = Some(milliseconds);
+        self
+    }
+
+    /// Create the pool.
+    ///
+    /// One connection is opened right away in order to validate the
+    /// configuration.
+    pub fn build(self) -> Result<ConnectionPool> {
+        if self.max_size == 0 {
+            raise!("the maximum size of a pool should be positive");
+        }
+        let inner = Inner {
+            path: self.path,
+            max_size: self.max_size,
+            busy_timeout: self.busy_timeout,
+            state: Mutex::new(State {
+                idle: vec![],
+                size: 0,
+            }),
+            available: Condvar::new(),
+        };
+        let connection = inner.open()?;
+        {
+            let mut state = lock(&inner.state);
+            state.idle.push(connection);
+            state.size = 1;
+        }
+        Ok(ConnectionPool {
+            inner: Arc::new(inner),
+        })
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.connection.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledConnection {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.connection.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            let mut state = lock(&self.pool.state);
+            if connection.is_autocommit() {
+                state.idle.push(connection);
+            } else {
+                state.size -= 1;
+            }
+            drop(state);
+            self.pool.available.notify_one();
+        }
+    }
+}
+
+impl Inner {
+    fn open(&self) -> Result<Connection> {
+        let mut connection = Connection::open(&self.path)?;
+        if let Some(milliseconds) = self.busy_timeout {
+            connection.set_busy_timeout(milliseconds)?;
+        }
+        Ok(connection)
+    }
+}
+
+#[inline]
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,97 @@
+use ffi;
+use libc::{c_char, c_int};
+
+use error::Result;
+
+type Get = unsafe extern "C" fn(
+    *mut ffi::sqlite3,
+    *const c_char,
+    *mut *mut ffi::sqlite3_snapshot,
+) -> c_int;
+type Open =
+    unsafe extern "C" fn(*mut ffi::sqlite3, *const c_char, *mut ffi::sqlite3_snapshot) -> c_int;
+type Recover = unsafe extern "C" fn(*mut ffi::sqlite3, *const c_char) -> c_int;
+type Free = unsafe extern "C" fn(*mut ffi::sqlite3_snapshot);
+
+// The functions are only present when SQLite is compiled with
+// SQLITE_ENABLE_SNAPSHOT, which most system libraries are not, so they are
+// looked up at run time instead of being linked against.
+#[cfg(unix)]
+macro_rules! symbol(
+    ($name:ident as $kind:ty) => ({
+        let pointer = unsafe {
+            ::libc::dlsym(
+                ::libc::RTLD_DEFAULT,
+                concat!(stringify!($name), "\0").as_ptr() as *const c_char,
+            )
+        };
+        if pointer.is_null() {
+            raise!("snapshots require SQLite compiled with SQLITE_ENABLE_SNAPSHOT");
+        }
+        unsafe { std::mem::transmute::<*mut ::libc::c_void, $kind>(pointer) }
+    });
+);
+
+#[cfg(not(unix))]
+macro_rules! symbol(
+    ($name:ident as $kind:ty) => (ffi::$name as $kind);
+);
+
+/// A snapshot of a database in the WAL mode.
+///
+/// The snapshot is freed when dropped.
+pub struct Snapshot {
+    raw: *mut ffi::sqlite3_snapshot,
+    free: Free,
+}
+
+unsafe impl Send for Snapshot {}
+
+impl Drop for Snapshot {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { (self.free)(self.raw) };
+    }
+}
+
+pub fn get(raw: *mut ffi::sqlite3, schema: &str) -> Result<Snapshot> {
+    check()?;
+    let get = symbol!(sqlite3_snapshot_get as Get);
+    let free = symbol!(sqlite3_snapshot_free as Free);
+    let mut snapshot = std::ptr::null_mut();
+    unsafe {
+        ok!(raw, get(raw, str_to_cstr!(schema).as_ptr(), &mut snapshot));
+    }
+    Ok(Snapshot {
+        raw: snapshot,
+        free,
+    })
+}
+
+pub fn open(raw: *mut ffi::sqlite3, schema: &str, snapshot: &Snapshot) -> Result<()> {
+    check()?;
+    let open = symbol!(sqlite3_snapshot_open as Open);
+    unsafe {
+        ok!(raw, open(raw, str_to_cstr!(schema).as_ptr(), snapshot.raw));
+    }
+    Ok(())
+}
+
+pub fn recover(raw: *mut ffi::sqlite3, schema: &str) -> Result<()> {
+    check()?;
+    let recover = symbol!(sqlite3_snapshot_recover as Recover);
+    unsafe {
+        ok!(raw, recover(raw, str_to_cstr!(schema).as_ptr()));
+    }
+    Ok(())
+}
+
+fn check() -> Result<()> {
+    if ::version() < 3022000 {
+        raise!("snapshots require SQLite 3.22 or newer");
+    }
+    if !::compile_option_used("ENABLE_SNAPSHOT") {
+        raise!("snapshots require SQLite compiled with SQLITE_ENABLE_SNAPSHOT");
+    }
+    Ok(())
+}
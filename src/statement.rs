@@ -1,22 +1,32 @@
 use ffi;
-use libc::{c_double, c_int};
+use libc::{c_char, c_double, c_int};
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
 use std::rc::Rc;
 
 use cursor::{Cursor, CursorWithOwnership, Row};
 use error::Result;
-use value::{Type, Value};
+use value::{Blob, ToSql, Type, Value};
 
-// https://sqlite.org/c3ref/c_static.html
-macro_rules! transient(
-    () => (::std::mem::transmute(!0 as *const ::libc::c_void));
-);
+// https://www.sqlite.org/c3ref/c_stmtstatus_counter.html
+const SQLITE_STMTSTATUS_REPREPARE: c_int = 5;
+const SQLITE_STMTSTATUS_RUN: c_int = 6;
+const SQLITE_STMTSTATUS_FILTER_MISS: c_int = 7;
+const SQLITE_STMTSTATUS_FILTER_HIT: c_int = 8;
+const SQLITE_STMTSTATUS_MEMUSED: c_int = 99;
+
+#[cfg(feature = "isexplain")]
+extern "C" {
+    // https://www.sqlite.org/c3ref/stmt_isexplain.html
+    fn sqlite3_stmt_isexplain(statement: *mut ffi::sqlite3_stmt) -> c_int;
+}
 
 /// A prepared statement.
 pub struct Statement<'l> {
     raw: (*mut ffi::sqlite3_stmt, *mut ffi::sqlite3),
-    column_names: Vec<String>,
+    column_names: Rc<Vec<String>>,
     column_mapping: Rc<HashMap<String, usize>>,
     phantom: PhantomData<(ffi::sqlite3_stmt, &'l ffi::sqlite3)>,
 }
@@ -59,6 +69,18 @@ pub trait ReadableWithIndex: Sized {
     fn read<T: ColumnIndex>(_: &Statement, _: T) -> Result<Self>;
 }
 
+/// The kind of EXPLAIN of a prepared statement.
+#[cfg(feature = "isexplain")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExplainMode {
+    /// The statement is an ordinary statement.
+    None,
+    /// The statement is an EXPLAIN statement.
+    Explain,
+    /// The statement is an EXPLAIN QUERY PLAN statement.
+    ExplainQueryPlan,
+}
+
 /// The state of a prepared statement.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum State {
@@ -68,6 +90,33 @@ pub enum State {
     Done,
 }
 
+/// A [status counter][1] of a prepared statement.
+///
+/// [1]: https://www.sqlite.org/c3ref/c_stmtstatus_counter.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StmtStatus {
+    /// The number of forward steps in full-table scans.
+    FullscanStep,
+    /// The number of sort operations.
+    Sort,
+    /// The number of rows inserted into automatic indices.
+    Autoindex,
+    /// The number of virtual-machine operations.
+    VmStep,
+    /// The number of times the statement has been automatically regenerated
+    /// due to schema changes.
+    Reprepare,
+    /// The number of times the statement has been run to completion or reset.
+    Run,
+    /// The number of times a Bloom filter has rejected a row.
+    FilterMiss,
+    /// The number of times a Bloom filter has failed to reject a row.
+    FilterHit,
+    /// The approximate number of bytes of heap memory used by the statement,
+    /// which is not affected by resetting.
+    Memused,
+}
+
 impl<'l> Statement<'l> {
     /// Bind values to parameters.
     ///
@@ -123,6 +172,16 @@ impl<'l> Statement<'l> {
     /// ][..])?;
     /// # Ok::<(), sqlite::Error>(())
     /// ```
+    ///
+    /// ```
+    /// # use sqlite::Value;
+    /// # let connection = sqlite::open(":memory:").unwrap();
+    /// # connection.execute("CREATE TABLE users (id INTEGER, name STRING)");
+    /// let query = "SELECT * FROM users WHERE id = ? AND name = ?";
+    /// let mut statement = connection.prepare(query)?;
+    /// statement.bind::<[Value; 2]>([1.into(), "Bob".into()])?;
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
     #[inline]
     pub fn bind<T: Bindable>(&mut self, value: T) -> Result<()> {
         value.bind(self)?;
@@ -156,6 +215,128 @@ impl<'l> Statement<'l> {
         Ok(())
     }
 
+    /// Bind a value to a parameter.
+    ///
+    /// In case of integer indices, the first parameter has index 1.
+    #[inline]
+    pub fn bind_value<T: ParameterIndex>(&mut self, index: T, value: &Value) -> Result<()> {
+        value.bind(self, index)
+    }
+
+    /// Bind a value converted via `ToSql` to a parameter.
+    ///
+    /// In case of integer indices, the first parameter has index 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::ToSql;
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (name TEXT, age INTEGER)")?;
+    /// let mut statement = connection.prepare("INSERT INTO users VALUES (?, ?)")?;
+    /// let values: [&dyn ToSql; 2] = [&"Alice", &Some(42)];
+    /// for (index, value) in values.iter().enumerate() {
+    ///     statement.bind_to_sql(index + 1, *value)?;
+    /// }
+    /// statement.next()?;
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn bind_to_sql<T: ParameterIndex>(&mut self, index: T, value: &dyn ToSql) -> Result<()> {
+        self.bind_value(index, &value.to_sql())
+    }
+
+    /// Bind values to parameters by position, starting from the first one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::Value;
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (id INTEGER, name TEXT)")?;
+    /// let mut statement = connection.prepare("INSERT INTO users VALUES (?, ?)")?;
+    /// let rows = vec![
+    ///     vec![Value::Integer(1), Value::String("Alice".into())],
+    ///     vec![Value::Integer(2), Value::String("Bob".into())],
+    /// ];
+    /// for row in &rows {
+    ///     statement.reset()?;
+    ///     statement.bind_values(row)?;
+    ///     statement.next()?;
+    /// }
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn bind_values(&mut self, values: &[Value]) -> Result<()> {
+        for (index, value) in values.iter().enumerate() {
+            self.bind_value(index + 1, value)?;
+        }
+        Ok(())
+    }
+
+    /// Bind a blob of a given size filled with zeros to a parameter.
+    ///
+    /// The blob takes no memory while bound, which makes it suitable for
+    /// reserving space to be filled via `Connection::open_blob`. In case of
+    /// integer indices, the first parameter has index 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE files (data BLOB)")?;
+    /// let mut statement = connection.prepare("INSERT INTO files VALUES (?)")?;
+    /// statement.bind_zeroblob(1, 5)?;
+    /// statement.next()?;
+    /// let rowid = connection.last_insert_rowid();
+    /// let mut blob = connection.open_blob("files", "data", rowid, true)?;
+    /// blob.write_all(b"Hello").unwrap();
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn bind_zeroblob<T: ParameterIndex>(&mut self, index: T, size: u64) -> Result<()> {
+        unsafe {
+            ok!(
+                self.raw.1,
+                ffi::sqlite3_bind_zeroblob64(
+                    self.raw.0,
+                    index.index(self)? as c_int,
+                    size as ffi::sqlite3_uint64,
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Bind a value to a parameter via the [pointer-passing interface][1].
+    ///
+    /// The value is not visible to SQL as such but can be retrieved by functions via `FunctionContext::get_pointer` given the
+    /// same type name. The value is dropped when the binding is cleared or
+    /// replaced or the statement is finalized. In case of integer indices, the
+    /// first parameter has index 1. The function requires SQLite 3.20 or
+    /// newer.
+    ///
+    /// [1]: https://www.sqlite.org/bindptr.html
+    #[cfg(feature = "pointer")]
+    pub fn bind_pointer<T: ParameterIndex, U: 'static>(
+        &mut self,
+        index: T,
+        value: Box<U>,
+        type_name: &'static str,
+    ) -> Result<()> {
+        let index = index.index(self)?;
+        ::function::bind_pointer(self.raw, index, value, type_name)
+    }
+
+    /// Set all parameters to NULL.
+    #[inline]
+    pub fn clear_bindings(&mut self) -> Result<()> {
+        unsafe { ok!(self.raw.1, ffi::sqlite3_clear_bindings(self.raw.0)) };
+        Ok(())
+    }
+
     /// Return the number of columns.
     #[inline]
     pub fn column_count(&self) -> usize {
@@ -168,6 +349,26 @@ impl<'l> Statement<'l> {
         self.column_mapping.clone()
     }
 
+    /// Return the name of the database a column originates from.
+    ///
+    /// `None` is returned if the column is not a column of a table, such as
+    /// an expression or an aggregate. In case of integer indices, the first
+    /// column has index 0.
+    #[inline]
+    pub fn column_database_name<T: ColumnIndex>(&self, index: T) -> Result<Option<&str>> {
+        column_metadata(self, index, ffi::sqlite3_column_database_name)
+    }
+
+    /// Return the declared type of a column.
+    ///
+    /// `None` is returned if the column is not a column of a table, such as
+    /// an expression, or if it has no declared type. In case of integer
+    /// indices, the first column has index 0.
+    #[inline]
+    pub fn column_decltype<T: ColumnIndex>(&self, index: T) -> Result<Option<&str>> {
+        column_metadata(self, index, ffi::sqlite3_column_decltype)
+    }
+
     /// Return the name of a column.
     ///
     /// In case of integer indices, the first column has index 0.
@@ -182,10 +383,39 @@ impl<'l> Statement<'l> {
         &self.column_names
     }
 
+    #[doc(hidden)]
+    #[inline]
+    pub fn column_names_shared(&self) -> Rc<Vec<String>> {
+        self.column_names.clone()
+    }
+
+    /// Return the name of a column in the table it originates from, which is
+    /// not affected by aliases.
+    ///
+    /// `None` is returned if the column is not a column of a table, such as
+    /// an expression or an aggregate. In case of integer indices, the first
+    /// column has index 0.
+    #[inline]
+    pub fn column_origin_name<T: ColumnIndex>(&self, index: T) -> Result<Option<&str>> {
+        column_metadata(self, index, ffi::sqlite3_column_origin_name)
+    }
+
+    /// Return the name of the table a column originates from.
+    ///
+    /// `None` is returned if the column is not a column of a table, such as
+    /// an expression or an aggregate. In case of integer indices, the first
+    /// column has index 0.
+    #[inline]
+    pub fn column_table_name<T: ColumnIndex>(&self, index: T) -> Result<Option<&str>> {
+        column_metadata(self, index, ffi::sqlite3_column_table_name)
+    }
+
     /// Return the type of a column.
     ///
-    /// The type becomes available after taking a step. In case of integer
-    /// indices, the first column has index 0.
+    /// The type is the storage class of the value in the current row, which
+    /// can differ from row to row and from the declared type given by
+    /// `column_decltype`. The type becomes available after taking a step. In
+    /// case of integer indices, the first column has index 0.
     pub fn column_type<T: ColumnIndex>(&self, index: T) -> Result<Type> {
         Ok(
             match unsafe { ffi::sqlite3_column_type(self.raw.0, index.index(self)? as c_int) } {
@@ -199,6 +429,61 @@ impl<'l> Statement<'l> {
         )
     }
 
+    /// Return the SQL text with bound parameters expanded.
+    ///
+    /// `None` is returned if the text cannot be produced, for instance, when
+    /// running out of memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let connection = sqlite::open(":memory:").unwrap();
+    /// # connection.execute("CREATE TABLE users (id INTEGER, name STRING)");
+    /// let query = "SELECT * FROM users WHERE id = ? AND name = :name";
+    /// let mut statement = connection.prepare(query)?;
+    /// statement.bind((1, 42))?;
+    /// statement.bind((":name", "Bob"))?;
+    /// assert_eq!(
+    ///     statement.expanded_sql().unwrap(),
+    ///     "SELECT * FROM users WHERE id = 42 AND name = 'Bob'",
+    /// );
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    pub fn expanded_sql(&self) -> Option<String> {
+        unsafe {
+            let pointer = ffi::sqlite3_expanded_sql(self.raw.0);
+            if pointer.is_null() {
+                return None;
+            }
+            let sql = c_str_to_string!(pointer);
+            ffi::sqlite3_free(pointer as *mut _);
+            Some(sql)
+        }
+    }
+
+    /// Return the kind of EXPLAIN of the statement.
+    ///
+    /// The function requires SQLite 3.28 or newer.
+    #[cfg(feature = "isexplain")]
+    #[inline]
+    pub fn is_explain(&self) -> ExplainMode {
+        match unsafe { sqlite3_stmt_isexplain(self.raw.0) } {
+            1 => ExplainMode::Explain,
+            2 => ExplainMode::ExplainQueryPlan,
+            _ => ExplainMode::None,
+        }
+    }
+
+    /// Check if the statement makes no direct changes to the database.
+    ///
+    /// Note that transaction-control statements such as `BEGIN`, `COMMIT`,
+    /// and `SAVEPOINT` are considered read-only, since they do not change the
+    /// content of the database by themselves.
+    #[inline]
+    pub fn is_readonly(&self) -> bool {
+        unsafe { ffi::sqlite3_stmt_readonly(self.raw.0) != 0 }
+    }
+
     /// Create a cursor.
     #[inline]
     pub fn iter(&mut self) -> Cursor<'l, '_> {
@@ -218,6 +503,15 @@ impl<'l> Statement<'l> {
         })
     }
 
+    /// Return the number of parameters.
+    ///
+    /// The largest index is returned in case of numbered parameters, such as
+    /// `?2`, which might leave gaps.
+    #[inline]
+    pub fn parameter_count(&self) -> usize {
+        unsafe { ffi::sqlite3_bind_parameter_count(self.raw.0) as usize }
+    }
+
     /// Return the index for a named parameter if exists.
     ///
     /// # Examples
@@ -242,6 +536,34 @@ impl<'l> Statement<'l> {
         }
     }
 
+    /// Return the name of a parameter if exists.
+    ///
+    /// The first parameter has index 1. Nameless parameters, such as `?`, have
+    /// no name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let connection = sqlite::open(":memory:").unwrap();
+    /// # connection.execute("CREATE TABLE users (id INTEGER, name STRING)");
+    /// let query = "SELECT * FROM users WHERE id = ? AND name = :name";
+    /// let statement = connection.prepare(query)?;
+    /// assert_eq!(statement.parameter_count(), 2);
+    /// assert_eq!(statement.parameter_name(1), None);
+    /// assert_eq!(statement.parameter_name(2), Some(":name"));
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn parameter_name(&self, index: usize) -> Option<&str> {
+        unsafe {
+            let pointer = ffi::sqlite3_bind_parameter_name(self.raw.0, index as c_int);
+            if pointer.is_null() {
+                return None;
+            }
+            c_str_to_str!(pointer).ok()
+        }
+    }
+
     /// Read a value from a column.
     ///
     /// In case of integer indices, the first column has index 0.
@@ -254,13 +576,36 @@ impl<'l> Statement<'l> {
         ReadableWithIndex::read(self, index)
     }
 
-    /// Reset the internal state.
+    /// Reset the internal state so that the statement can be executed again.
+    ///
+    /// Bound parameters are kept; see `clear_bindings`.
     #[inline]
     pub fn reset(&mut self) -> Result<()> {
         unsafe { ok!(self.raw.1, ffi::sqlite3_reset(self.raw.0)) };
         Ok(())
     }
 
+    /// Return the value of a status counter.
+    ///
+    /// If `reset` is `true`, the counter is reset to zero after being read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sqlite::{State, StmtStatus};
+    ///
+    /// let connection = sqlite::open(":memory:")?;
+    /// connection.execute("CREATE TABLE users (id INTEGER PRIMARY KEY)")?;
+    /// let mut statement = connection.prepare("SELECT * FROM users WHERE id = 42")?;
+    /// assert_eq!(statement.next()?, State::Done);
+    /// assert_eq!(statement.status(StmtStatus::FullscanStep, false), 0);
+    /// # Ok::<(), sqlite::Error>(())
+    /// ```
+    #[inline]
+    pub fn status(&self, status: StmtStatus, reset: bool) -> i32 {
+        unsafe { ffi::sqlite3_stmt_status(self.raw.0, status.as_raw(), reset as c_int) as i32 }
+    }
+
     #[doc(hidden)]
     #[inline]
     pub fn as_raw(&self) -> *mut ffi::sqlite3_stmt {
@@ -268,6 +613,39 @@ impl<'l> Statement<'l> {
     }
 }
 
+impl StmtStatus {
+    fn as_raw(&self) -> c_int {
+        match self {
+            StmtStatus::FullscanStep => ffi::SQLITE_STMTSTATUS_FULLSCAN_STEP,
+            StmtStatus::Sort => ffi::SQLITE_STMTSTATUS_SORT,
+            StmtStatus::Autoindex => ffi::SQLITE_STMTSTATUS_AUTOINDEX,
+            StmtStatus::VmStep => ffi::SQLITE_STMTSTATUS_VM_STEP,
+            StmtStatus::Reprepare => SQLITE_STMTSTATUS_REPREPARE,
+            StmtStatus::Run => SQLITE_STMTSTATUS_RUN,
+            StmtStatus::FilterMiss => SQLITE_STMTSTATUS_FILTER_MISS,
+            StmtStatus::FilterHit => SQLITE_STMTSTATUS_FILTER_HIT,
+            StmtStatus::Memused => SQLITE_STMTSTATUS_MEMUSED,
+        }
+    }
+}
+
+impl<'l> std::fmt::Debug for Statement<'l> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let sql = unsafe {
+            let pointer = ffi::sqlite3_sql(self.raw.0);
+            if pointer.is_null() {
+                None
+            } else {
+                c_str_to_str!(pointer).ok()
+            }
+        };
+        formatter
+            .debug_struct("Statement")
+            .field("sql", &sql)
+            .finish()
+    }
+}
+
 impl<'l> Drop for Statement<'l> {
     #[inline]
     fn drop(&mut self) {
@@ -303,6 +681,38 @@ where
     }
 }
 
+impl Bindable for () {
+    #[inline]
+    fn bind(self, _: &mut Statement) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> Bindable for [T; N]
+where
+    T: BindableWithIndex,
+{
+    fn bind(self, statement: &mut Statement) -> Result<()> {
+        for (index, value) in IntoIterator::into_iter(self).enumerate() {
+            value.bind(statement, index + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, U, const N: usize> Bindable for [(T, U); N]
+where
+    T: ParameterIndex,
+    U: BindableWithIndex,
+{
+    fn bind(self, statement: &mut Statement) -> Result<()> {
+        for (index, value) in IntoIterator::into_iter(self) {
+            value.bind(statement, index)?;
+        }
+        Ok(())
+    }
+}
+
 impl<T> Bindable for &[T]
 where
     T: BindableWithIndex + Clone,
@@ -328,6 +738,13 @@ where
     }
 }
 
+impl BindableWithIndex for Blob {
+    #[inline]
+    fn bind<T: ParameterIndex>(self, statement: &mut Statement, index: T) -> Result<()> {
+        (&self.0 as &[u8]).bind(statement, index)
+    }
+}
+
 impl BindableWithIndex for &[u8] {
     #[inline]
     fn bind<T: ParameterIndex>(self, statement: &mut Statement, index: T) -> Result<()> {
@@ -413,6 +830,13 @@ impl BindableWithIndex for () {
     }
 }
 
+impl BindableWithIndex for &dyn ToSql {
+    #[inline]
+    fn bind<T: ParameterIndex>(self, statement: &mut Statement, index: T) -> Result<()> {
+        statement.bind_to_sql(index, self)
+    }
+}
+
 impl BindableWithIndex for Value {
     #[inline]
     fn bind<T: ParameterIndex>(self, statement: &mut Statement, index: T) -> Result<()> {
@@ -427,7 +851,7 @@ impl BindableWithIndex for &Value {
             Value::Float(value) => value.bind(statement, index),
             Value::Integer(value) => value.bind(statement, index),
             Value::String(ref value) => (value as &str).bind(statement, index),
-            Value::Null => ().bind(statement, index),
+            Value::Null => BindableWithIndex::bind((), statement, index),
         }
     }
 }
@@ -440,7 +864,7 @@ where
     fn bind<U: ParameterIndex>(self, statement: &mut Statement, index: U) -> Result<()> {
         match self {
             Some(value) => value.bind(statement, index),
-            None => ().bind(statement, index),
+            None => BindableWithIndex::bind((), statement, index),
         }
     }
 }
@@ -453,7 +877,7 @@ where
     fn bind<U: ParameterIndex>(self, statement: &mut Statement, index: U) -> Result<()> {
         match self {
             Some(value) => value.clone().bind(statement, index),
-            None => ().bind(statement, index),
+            None => BindableWithIndex::bind((), statement, index),
         }
     }
 }
@@ -469,6 +893,23 @@ impl ColumnIndex for &str {
     }
 }
 
+impl ColumnIndex for &String {
+    #[inline]
+    fn index(self, statement: &Statement) -> Result<usize> {
+        ColumnIndex::index(self.as_str(), statement)
+    }
+}
+
+impl ColumnIndex for i32 {
+    #[inline]
+    fn index(self, statement: &Statement) -> Result<usize> {
+        match usize::try_from(self) {
+            Ok(index) => ColumnIndex::index(index, statement),
+            _ => raise!("the index is out of range ({})", self),
+        }
+    }
+}
+
 impl ColumnIndex for usize {
     #[inline]
     fn index(self, statement: &Statement) -> Result<usize> {
@@ -513,6 +954,13 @@ impl ReadableWithIndex for Value {
     }
 }
 
+impl ReadableWithIndex for f32 {
+    #[inline]
+    fn read<T: ColumnIndex>(statement: &Statement, index: T) -> Result<Self> {
+        f64::read(statement, index).map(|value| value as f32)
+    }
+}
+
 impl ReadableWithIndex for f64 {
     #[inline]
     #[allow(clippy::unnecessary_cast)]
@@ -533,6 +981,29 @@ impl ReadableWithIndex for i64 {
     }
 }
 
+macro_rules! implement(
+    ($type:ty) => {
+        impl ReadableWithIndex for $type {
+            #[inline]
+            fn read<T: ColumnIndex>(statement: &Statement, index: T) -> Result<Self> {
+                let value = i64::read(statement, index)?;
+                match <$type>::try_from(value) {
+                    Ok(value) => Ok(value),
+                    _ => raise!("the integer is out of range ({})", value),
+                }
+            }
+        }
+    };
+);
+
+implement!(i8);
+implement!(i16);
+implement!(i32);
+implement!(u8);
+implement!(u16);
+implement!(u32);
+implement!(u64);
+
 impl ReadableWithIndex for String {
     #[inline]
     fn read<T: ColumnIndex>(statement: &Statement, index: T) -> Result<Self> {
@@ -547,6 +1018,16 @@ impl ReadableWithIndex for String {
     }
 }
 
+impl ReadableWithIndex for Blob {
+    #[inline]
+    fn read<T: ColumnIndex>(statement: &Statement, index: T) -> Result<Self> {
+        if statement.column_type(index)? != Type::Binary {
+            raise!("failed to read a blob from a column of another type");
+        }
+        Vec::<u8>::read(statement, index).map(Blob)
+    }
+}
+
 impl ReadableWithIndex for Vec<u8> {
     #[inline]
     fn read<T: ColumnIndex>(statement: &Statement, index: T) -> Result<Self> {
@@ -596,6 +1077,13 @@ where
             )
         );
     }
+    Ok(from_raw(raw_statement, raw_connection))
+}
+
+pub fn from_raw<'l>(
+    raw_statement: *mut ffi::sqlite3_stmt,
+    raw_connection: *mut ffi::sqlite3,
+) -> Statement<'l> {
     let column_count = unsafe { ffi::sqlite3_column_count(raw_statement) as usize };
     let column_names = (0..column_count)
         .map(|index| unsafe {
@@ -609,10 +1097,34 @@ where
         .enumerate()
         .map(|(index, name)| (name.to_string(), index))
         .collect();
-    Ok(Statement {
+    Statement {
         raw: (raw_statement, raw_connection),
-        column_names,
+        column_names: Rc::new(column_names),
         column_mapping: Rc::new(column_mapping),
         phantom: PhantomData,
-    })
+    }
+}
+
+pub fn into_raw(statement: Statement<'_>) -> *mut ffi::sqlite3_stmt {
+    let mut statement = ManuallyDrop::new(statement);
+    unsafe {
+        std::ptr::drop_in_place(&mut statement.column_names);
+        std::ptr::drop_in_place(&mut statement.column_mapping);
+    }
+    statement.raw.0
+}
+
+fn column_metadata<'m, T: ColumnIndex>(
+    statement: &'m Statement,
+    index: T,
+    function: unsafe extern "C" fn(*mut ffi::sqlite3_stmt, c_int) -> *const c_char,
+) -> Result<Option<&'m str>> {
+    let index = index.index(statement)?;
+    unsafe {
+        let pointer = function(statement.raw.0, index as c_int);
+        if pointer.is_null() {
+            return Ok(None);
+        }
+        Ok(c_str_to_str!(pointer).ok())
+    }
 }
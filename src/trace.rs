@@ -0,0 +1,103 @@
+use ffi;
+use libc::{c_char, c_int, c_uint, c_void};
+
+/// Flags for selecting the events to trace.
+#[derive(Clone, Copy, Debug)]
+pub struct TraceFlags(c_int);
+
+/// A traced event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceEvent<'l> {
+    /// A statement has started running.
+    ///
+    /// For statements run by triggers, the text is a comment identifying the
+    /// trigger.
+    Statement { sql: &'l str },
+    /// A statement has finished running.
+    Profile { sql: &'l str, nanoseconds: u64 },
+    /// A statement has produced a row.
+    Row { sql: &'l str },
+    /// The connection has been closed.
+    Close,
+}
+
+impl TraceFlags {
+    /// Create flags selecting no events.
+    #[inline]
+    pub fn new() -> Self {
+        TraceFlags(0)
+    }
+
+    /// Trace the closing of the connection.
+    pub fn set_close(mut self) -> Self {
+        self.0 |= ffi::SQLITE_TRACE_CLOSE;
+        self
+    }
+
+    /// Trace the completion of statements along with their running time.
+    pub fn set_profile(mut self) -> Self {
+        self.0 |= ffi::SQLITE_TRACE_PROFILE;
+        self
+    }
+
+    /// Trace the rows produced by statements.
+    pub fn set_row(mut self) -> Self {
+        self.0 |= ffi::SQLITE_TRACE_ROW;
+        self
+    }
+
+    /// Trace the start of statements.
+    pub fn set_statement(mut self) -> Self {
+        self.0 |= ffi::SQLITE_TRACE_STMT;
+        self
+    }
+}
+
+impl Default for TraceFlags {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+pub fn flags(flags: TraceFlags) -> c_uint {
+    flags.0 as c_uint
+}
+
+pub extern "C" fn callback<F>(
+    code: c_uint,
+    callback: *mut c_void,
+    first: *mut c_void,
+    second: *mut c_void,
+) -> c_int
+where
+    F: FnMut(TraceEvent<'_>),
+{
+    unsafe {
+        let event = match code as c_int {
+            ffi::SQLITE_TRACE_STMT => TraceEvent::Statement {
+                sql: read(second as *const c_char),
+            },
+            ffi::SQLITE_TRACE_PROFILE => TraceEvent::Profile {
+                sql: read(ffi::sqlite3_sql(first as *mut ffi::sqlite3_stmt)),
+                nanoseconds: *(second as *const i64) as u64,
+            },
+            ffi::SQLITE_TRACE_ROW => TraceEvent::Row {
+                sql: read(ffi::sqlite3_sql(first as *mut ffi::sqlite3_stmt)),
+            },
+            ffi::SQLITE_TRACE_CLOSE => TraceEvent::Close,
+            _ => return 0,
+        };
+        ::connection::guard((), || (*(callback as *mut F))(event));
+    }
+    0
+}
+
+unsafe fn read<'l>(pointer: *const c_char) -> &'l str {
+    if pointer.is_null() {
+        ""
+    } else {
+        c_str_to_str!(pointer).unwrap_or_default()
+    }
+}
@@ -0,0 +1,123 @@
+use std::ops::Deref;
+
+use connection::Connection;
+use error::Result;
+
+/// A transaction.
+///
+/// The transaction is rolled back when dropped unless it has been committed.
+pub struct Transaction<'l> {
+    connection: &'l mut Connection,
+    done: bool,
+}
+
+/// A savepoint within a transaction.
+///
+/// The savepoint is rolled back when dropped unless it has been released.
+pub struct Savepoint<'l> {
+    connection: &'l mut Connection,
+    name: String,
+    done: bool,
+}
+
+macro_rules! implement(
+    ($type:ident<$lifetime:lifetime>) => {
+        impl<$lifetime> $type<$lifetime> {
+            /// Create a savepoint nested within the current one.
+            pub fn savepoint(&mut self, name: &str) -> Result<Savepoint<'_>> {
+                new_savepoint(self.connection, name)
+            }
+        }
+
+        impl<$lifetime> Deref for $type<$lifetime> {
+            type Target = Connection;
+
+            #[inline]
+            fn deref(&self) -> &Self::Target {
+                self.connection
+            }
+        }
+    }
+);
+
+implement!(Transaction<'l>);
+implement!(Savepoint<'l>);
+
+impl<'l> Transaction<'l> {
+    /// Commit the transaction.
+    pub fn commit(mut self) -> Result<()> {
+        self.connection.execute("COMMIT")?;
+        self.done = true;
+        Ok(())
+    }
+
+    /// Roll back the transaction.
+    pub fn rollback(mut self) -> Result<()> {
+        self.connection.execute("ROLLBACK")?;
+        self.done = true;
+        Ok(())
+    }
+}
+
+impl<'l> Drop for Transaction<'l> {
+    #[inline]
+    #[allow(unused_must_use)]
+    fn drop(&mut self) {
+        if !self.done {
+            self.connection.execute("ROLLBACK");
+        }
+    }
+}
+
+impl<'l> Savepoint<'l> {
+    /// Release the savepoint, which merges its changes into the enclosing
+    /// transaction.
+    pub fn release(mut self) -> Result<()> {
+        self.connection
+            .execute(format!("RELEASE {}", quote(&self.name)))?;
+        self.done = true;
+        Ok(())
+    }
+
+    /// Roll back the savepoint.
+    pub fn rollback(mut self) -> Result<()> {
+        let name = quote(&self.name);
+        self.connection
+            .execute(format!("ROLLBACK TO {name}; RELEASE {name}"))?;
+        self.done = true;
+        Ok(())
+    }
+}
+
+impl<'l> Drop for Savepoint<'l> {
+    #[inline]
+    #[allow(unused_must_use)]
+    fn drop(&mut self) {
+        if !self.done {
+            let name = quote(&self.name);
+            self.connection
+                .execute(format!("ROLLBACK TO {name}; RELEASE {name}"));
+        }
+    }
+}
+
+pub fn new(connection: &mut Connection) -> Result<Transaction<'_>> {
+    connection.execute("BEGIN")?;
+    Ok(Transaction {
+        connection,
+        done: false,
+    })
+}
+
+fn new_savepoint<'l>(connection: &'l mut Connection, name: &str) -> Result<Savepoint<'l>> {
+    connection.execute(format!("SAVEPOINT {}", quote(name)))?;
+    Ok(Savepoint {
+        connection,
+        name: name.to_string(),
+        done: false,
+    })
+}
+
+pub fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
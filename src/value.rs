@@ -1,9 +1,18 @@
+use std::cmp::Ordering;
 use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
 use {Error, Result};
 
 /// A value.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Values are ordered as in SQLite: null comes first, followed by numbers,
+/// strings, and binary data. Integers and floating-point numbers are compared
+/// by their numeric values, with an integer coming before an equal
+/// floating-point number so that the ordering agrees with equality. Unlike in
+/// SQL, null is equal to null; see `SqlEqual` for the SQL semantics.
+#[derive(Clone, Debug)]
 pub enum Value {
     /// Binary data.
     Binary(Vec<u8>),
@@ -17,6 +26,22 @@ pub enum Value {
     Null,
 }
 
+/// A value compared as in SQL.
+///
+/// Null is not equal to anything, including null, and integers are equal to
+/// floating-point numbers with the same numeric value.
+///
+/// # Examples
+///
+/// ```
+/// use sqlite::{SqlEqual, Value};
+///
+/// assert!(SqlEqual(Value::Integer(1)) == SqlEqual(Value::Float(1.0)));
+/// assert!(SqlEqual(Value::Null) != SqlEqual(Value::Null));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SqlEqual(pub Value);
+
 /// The type of a value.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Type {
@@ -32,7 +57,114 @@ pub enum Type {
     Null,
 }
 
+/// Binary data read strictly from values of the binary type.
+///
+/// Unlike `Vec<u8>`, which also accepts the bytes of strings, the conversion
+/// fails for values of any type other than binary.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Blob(pub Vec<u8>);
+
+/// A type that can be constructed from a value.
+///
+/// Unlike `TryFrom<&Value>`, the result cannot borrow from the value, which
+/// makes the trait suitable for implementing on types defined elsewhere; see
+/// `Row::try_get`.
+pub trait FromSql: Sized {
+    /// Construct from a value.
+    fn from_sql(value: &Value) -> Result<Self>;
+}
+
+/// A type that can be converted into a value for binding to parameters.
+///
+/// Unlike `Into<Value>`, the conversion borrows the original, which allows for
+/// binding trait objects; see `Statement::bind_to_sql`.
+pub trait ToSql {
+    /// Convert into a value.
+    fn to_sql(&self) -> Value;
+}
+
 impl Value {
+    /// Convert the value to an integer following the [numeric affinity][1]
+    /// rules of SQLite.
+    ///
+    /// Floating-point numbers, including those given as strings, are
+    /// truncated toward zero as with `CAST`. `None` is returned for strings
+    /// that do not look like numbers as well as for binary data and null.
+    ///
+    /// [1]: https://www.sqlite.org/datatype3.html#type_affinity
+    pub fn coerce_to_integer(&self) -> Option<i64> {
+        match *self {
+            Value::Float(value) => truncate(value),
+            Value::Integer(value) => Some(value),
+            Value::String(ref value) => {
+                let value = numeric(value)?;
+                match value.parse::<i64>() {
+                    Ok(value) => Some(value),
+                    _ => value.parse::<f64>().ok().and_then(truncate),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert the value to a floating-point number following the [numeric
+    /// affinity][1] rules of SQLite.
+    ///
+    /// `None` is returned for strings that do not look like numbers as well as
+    /// for binary data and null.
+    ///
+    /// [1]: https://www.sqlite.org/datatype3.html#type_affinity
+    pub fn coerce_to_float(&self) -> Option<f64> {
+        match *self {
+            Value::Float(value) => Some(value),
+            Value::Integer(value) => Some(value as f64),
+            Value::String(ref value) => numeric(value)?.parse::<f64>().ok(),
+            _ => None,
+        }
+    }
+
+    /// Check if the value is binary data.
+    #[inline]
+    pub fn is_binary(&self) -> bool {
+        self.kind() == Type::Binary
+    }
+
+    /// Check if the value is a floating-point number.
+    #[inline]
+    pub fn is_float(&self) -> bool {
+        self.kind() == Type::Float
+    }
+
+    /// Check if the value is an integer number.
+    #[inline]
+    pub fn is_integer(&self) -> bool {
+        self.kind() == Type::Integer
+    }
+
+    /// Check if the value is null.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.kind() == Type::Null
+    }
+
+    /// Check if the value is a string.
+    #[inline]
+    pub fn is_text(&self) -> bool {
+        self.kind() == Type::String
+    }
+
+    /// Return the name of the type as used by SQLite, such as `INTEGER`.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Binary(_) => "BLOB",
+            Value::Float(_) => "REAL",
+            Value::Integer(_) => "INTEGER",
+            Value::String(_) => "TEXT",
+            Value::Null => "NULL",
+        }
+    }
+
     /// Return the type.
     pub fn kind(&self) -> Type {
         match self {
@@ -54,6 +186,87 @@ impl Value {
     }
 }
 
+impl PartialEq for Value {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Binary(first), Value::Binary(second)) => first.cmp(second),
+            (Value::Float(first), Value::Float(second)) => compare_floats(*first, *second),
+            (Value::Float(first), Value::Integer(second)) => compare_numbers(*second, *first)
+                .reverse()
+                .then(Ordering::Greater),
+            (Value::Integer(first), Value::Float(second)) => {
+                compare_numbers(*first, *second).then(Ordering::Less)
+            }
+            (Value::Integer(first), Value::Integer(second)) => first.cmp(second),
+            (Value::String(first), Value::String(second)) => first.cmp(second),
+            (Value::Null, Value::Null) => Ordering::Equal,
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        rank(self).hash(state);
+        match *self {
+            Value::Binary(ref value) => value.hash(state),
+            Value::Float(value) if value.is_nan() => f64::NAN.to_bits().hash(state),
+            Value::Float(value) => (value + 0.0).to_bits().hash(state),
+            Value::Integer(value) => value.hash(state),
+            Value::String(ref value) => value.hash(state),
+            Value::Null => {}
+        }
+    }
+}
+
+impl PartialEq for SqlEqual {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Value::Null, _) | (_, Value::Null) => false,
+            (Value::Float(value), _) | (_, Value::Float(value)) if value.is_nan() => false,
+            (Value::Float(first), Value::Integer(second))
+            | (Value::Integer(second), Value::Float(first)) => {
+                compare_numbers(*second, *first) == Ordering::Equal
+            }
+            (first, second) => first == second,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Binary(ref value) => {
+                formatter.write_str("X'")?;
+                for byte in value {
+                    write!(formatter, "{:02X}", byte)?;
+                }
+                formatter.write_str("'")
+            }
+            Value::Float(value) => write!(formatter, "{:?}", value),
+            Value::Integer(value) => value.fmt(formatter),
+            Value::String(ref value) => formatter.write_str(value),
+            Value::Null => formatter.write_str("NULL"),
+        }
+    }
+}
+
 macro_rules! implement(
     ($type:ty, Null) => {
         impl From<$type> for Value {
@@ -133,6 +346,34 @@ macro_rules! implement(
             }
         }
     };
+    (@integer $type:ty) => {
+        impl TryFrom<&Value> for $type {
+            type Error = Error;
+
+            #[inline]
+            fn try_from(value: &Value) -> Result<Self> {
+                if let &Value::Integer(value) = value {
+                    return match <$type>::try_from(value) {
+                        Ok(value) => Ok(value),
+                        _ => raise!("the integer is out of range ({})", value),
+                    };
+                }
+                raise!("failed to convert");
+            }
+        }
+
+        impl TryFrom<&Value> for Option<$type> {
+            type Error = Error;
+
+            #[inline]
+            fn try_from(value: &Value) -> Result<Self> {
+                if let &Value::Null = value {
+                    return Ok(None);
+                }
+                <$type>::try_from(value).map(Some)
+            }
+        }
+    };
     (@reference-lifetime $type:ty, $value:ident) => {
         impl<'l> TryFrom<&'l Value> for $type {
             type Error = Error;
@@ -160,10 +401,346 @@ macro_rules! implement(
     };
 );
 
-implement!(@value Vec<u8>, Binary);
 implement!(@reference-lifetime &'l [u8], Binary);
 implement!(@value String, String);
 implement!(@reference-lifetime &'l str, String);
 implement!(@reference f64, Float);
 implement!(@reference i64, Integer);
 implement!(@reference (), Null);
+implement!(@integer i8);
+implement!(@integer i16);
+implement!(@integer i32);
+implement!(@integer u8);
+implement!(@integer u16);
+implement!(@integer u32);
+implement!(@integer u64);
+
+impl TryFrom<&Value> for String {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        <&str>::try_from(value).map(String::from)
+    }
+}
+
+impl TryFrom<&Value> for Option<String> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        <Option<&str>>::try_from(value).map(|value| value.map(String::from))
+    }
+}
+
+impl TryFrom<Value> for Vec<u8> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: Value) -> Result<Self> {
+        match value {
+            Value::Binary(value) => Ok(value),
+            Value::String(value) => Ok(value.into_bytes()),
+            _ => raise!("failed to convert"),
+        }
+    }
+}
+
+impl TryFrom<&Value> for Vec<u8> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        match *value {
+            Value::Binary(ref value) => Ok(value.clone()),
+            Value::String(ref value) => Ok(value.as_bytes().to_vec()),
+            _ => raise!("failed to convert"),
+        }
+    }
+}
+
+impl TryFrom<&Value> for Option<Vec<u8>> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        if let &Value::Null = value {
+            return Ok(None);
+        }
+        <Vec<u8>>::try_from(value).map(Some)
+    }
+}
+
+impl From<Blob> for Value {
+    #[inline]
+    fn from(value: Blob) -> Self {
+        Value::Binary(value.0)
+    }
+}
+
+impl TryFrom<&Value> for Blob {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        <&[u8]>::try_from(value).map(|value| Blob(value.to_vec()))
+    }
+}
+
+impl TryFrom<&Value> for Option<Blob> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        if let &Value::Null = value {
+            return Ok(None);
+        }
+        Blob::try_from(value).map(Some)
+    }
+}
+
+impl TryFrom<&Value> for f32 {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        f64::try_from(value).map(|value| value as f32)
+    }
+}
+
+impl TryFrom<&Value> for Option<f32> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        if let &Value::Null = value {
+            return Ok(None);
+        }
+        f32::try_from(value).map(Some)
+    }
+}
+
+impl TryFrom<&Value> for bool {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        match *value {
+            Value::Integer(0) => Ok(false),
+            Value::Integer(1) => Ok(true),
+            _ => raise!("failed to convert"),
+        }
+    }
+}
+
+impl TryFrom<&Value> for Option<bool> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self> {
+        if let &Value::Null = value {
+            return Ok(None);
+        }
+        bool::try_from(value).map(Some)
+    }
+}
+
+macro_rules! implement(
+    ($type:ty) => {
+        impl FromSql for $type {
+            #[inline]
+            fn from_sql(value: &Value) -> Result<Self> {
+                <$type>::try_from(value)
+            }
+        }
+    };
+);
+
+implement!(Blob);
+implement!(String);
+implement!(Vec<u8>);
+implement!(bool);
+implement!(f32);
+implement!(f64);
+implement!(i8);
+implement!(i16);
+implement!(i32);
+implement!(i64);
+implement!(u8);
+implement!(u16);
+implement!(u32);
+implement!(u64);
+
+impl FromSql for Value {
+    #[inline]
+    fn from_sql(value: &Value) -> Result<Self> {
+        Ok(value.clone())
+    }
+}
+
+impl<T: FromSql> FromSql for Option<T> {
+    #[inline]
+    fn from_sql(value: &Value) -> Result<Self> {
+        match *value {
+            Value::Null => Ok(None),
+            _ => T::from_sql(value).map(Some),
+        }
+    }
+}
+
+macro_rules! implement(
+    (@integer $type:ty) => {
+        impl ToSql for $type {
+            #[inline]
+            fn to_sql(&self) -> Value {
+                Value::Integer(i64::from(*self))
+            }
+        }
+    };
+    (@float $type:ty) => {
+        impl ToSql for $type {
+            #[inline]
+            fn to_sql(&self) -> Value {
+                Value::Float(f64::from(*self))
+            }
+        }
+    };
+);
+
+implement!(@integer bool);
+implement!(@integer i8);
+implement!(@integer i16);
+implement!(@integer i32);
+implement!(@integer i64);
+implement!(@integer u8);
+implement!(@integer u16);
+implement!(@integer u32);
+implement!(@float f32);
+implement!(@float f64);
+
+impl ToSql for str {
+    #[inline]
+    fn to_sql(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToSql for String {
+    #[inline]
+    fn to_sql(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl ToSql for [u8] {
+    #[inline]
+    fn to_sql(&self) -> Value {
+        Value::Binary(self.to_vec())
+    }
+}
+
+impl ToSql for Vec<u8> {
+    #[inline]
+    fn to_sql(&self) -> Value {
+        Value::Binary(self.clone())
+    }
+}
+
+impl ToSql for Blob {
+    #[inline]
+    fn to_sql(&self) -> Value {
+        Value::Binary(self.0.clone())
+    }
+}
+
+impl ToSql for Value {
+    #[inline]
+    fn to_sql(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl<T: ToSql> ToSql for Option<T> {
+    #[inline]
+    fn to_sql(&self) -> Value {
+        match *self {
+            Some(ref value) => value.to_sql(),
+            _ => Value::Null,
+        }
+    }
+}
+
+impl<T: ToSql + ?Sized> ToSql for &T {
+    #[inline]
+    fn to_sql(&self) -> Value {
+        (**self).to_sql()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Value::Binary(ref value) => serializer.serialize_bytes(value),
+            Value::Float(value) => serializer.serialize_f64(*value),
+            Value::Integer(value) => serializer.serialize_i64(*value),
+            Value::String(ref value) => serializer.serialize_str(value),
+            Value::Null => serializer.serialize_none(),
+        }
+    }
+}
+
+fn numeric(value: &str) -> Option<&str> {
+    let value = value.trim();
+    let valid = value.bytes().any(|byte| byte.is_ascii_digit())
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || b"+-.eE".contains(&byte));
+    if valid {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+fn truncate(value: f64) -> Option<i64> {
+    if value.is_nan() {
+        None
+    } else {
+        Some(value as i64)
+    }
+}
+
+fn compare_floats(first: f64, second: f64) -> Ordering {
+    match (first.is_nan(), second.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => first.partial_cmp(&second).unwrap(),
+    }
+}
+
+fn compare_numbers(first: i64, second: f64) -> Ordering {
+    const LIMIT: f64 = 9223372036854775808.0;
+    if second.is_nan() || second < -LIMIT {
+        return Ordering::Greater;
+    }
+    if second >= LIMIT {
+        return Ordering::Less;
+    }
+    first
+        .cmp(&(second.trunc() as i64))
+        .then_with(|| 0.0.partial_cmp(&second.fract()).unwrap())
+}
+
+fn rank(value: &Value) -> u8 {
+    match *value {
+        Value::Null => 0,
+        Value::Float(_) | Value::Integer(_) => 1,
+        Value::String(_) => 2,
+        Value::Binary(_) => 3,
+    }
+}
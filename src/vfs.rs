@@ -0,0 +1,52 @@
+use ffi;
+use libc::{c_char, c_int};
+
+use error::{Error, Result};
+
+// The type is opaque in sqlite3-sys, so the leading fields of sqlite3_vfs up
+// to zName, which are the same in all versions of the structure, are mirrored.
+//
+// https://www.sqlite.org/c3ref/vfs.html
+#[repr(C)]
+struct Header {
+    version: c_int,
+    file_size: c_int,
+    path_size: c_int,
+    next: *mut ffi::sqlite3_vfs,
+    name: *const c_char,
+}
+
+/// Make a registered [VFS][1] the default one for new connections.
+///
+/// Note that this is a process-global setting, which affects all databases
+/// opened afterwards in the process, including those opened outside of this
+/// crate.
+///
+/// [1]: https://www.sqlite.org/vfs.html
+pub fn set_default_vfs(name: &str) -> Result<()> {
+    unsafe {
+        let vfs = ffi::sqlite3_vfs_find(str_to_cstr!(name).as_ptr());
+        if vfs.is_null() {
+            raise!("failed to find a VFS ({})", name);
+        }
+        match ffi::sqlite3_vfs_register(vfs, 1) {
+            ffi::SQLITE_OK => Ok(()),
+            code => Err(Error::from_code(code)),
+        }
+    }
+}
+
+/// Return the name of the default VFS.
+pub fn get_default_vfs() -> Option<String> {
+    unsafe {
+        let vfs = ffi::sqlite3_vfs_find(std::ptr::null());
+        if vfs.is_null() {
+            return None;
+        }
+        let name = (*(vfs as *const Header)).name;
+        if name.is_null() {
+            return None;
+        }
+        Some(c_str_to_string!(name))
+    }
+}
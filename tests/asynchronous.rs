@@ -0,0 +1,83 @@
+#![cfg(feature = "tokio")]
+
+extern crate sqlite;
+extern crate tokio;
+
+use sqlite::{AsyncConnection, State};
+use tokio::runtime::{Builder, Runtime};
+
+macro_rules! ok(($result:expr) => ($result.unwrap()));
+
+fn setup() -> (Runtime, AsyncConnection) {
+    let runtime = ok!(Builder::new_current_thread().build());
+    let connection = ok!(runtime.block_on(AsyncConnection::open(":memory:")));
+    ok!(runtime.block_on(connection.execute(
+        "
+        CREATE TABLE users (id INTEGER, name TEXT);
+        INSERT INTO users VALUES (1, 'Alice');
+        "
+    )));
+    (runtime, connection)
+}
+
+fn count(runtime: &Runtime, connection: &AsyncConnection) -> i64 {
+    ok!(runtime.block_on(
+        connection.query_row("SELECT COUNT(*) FROM users", (), |row| {
+            row.try_read::<i64, _>(0)
+        })
+    ))
+}
+
+#[test]
+fn call() {
+    let (runtime, connection) = setup();
+    let names = ok!(runtime.block_on(connection.call(|connection| {
+        let mut statement = connection.prepare("SELECT name FROM users")?;
+        let mut names = vec![];
+        while let State::Row = statement.next()? {
+            names.push(statement.read::<String, _>(0)?);
+        }
+        Ok(names)
+    })));
+    assert_eq!(names, vec!["Alice"]);
+}
+
+#[test]
+fn execute() {
+    let (runtime, connection) = setup();
+    let other = connection.clone();
+    ok!(runtime.block_on(other.execute("INSERT INTO users VALUES (2, 'Bob')")));
+    assert_eq!(count(&runtime, &connection), 2);
+    assert!(runtime.block_on(connection.execute(":)")).is_err());
+}
+
+#[test]
+fn query_row() {
+    let (runtime, connection) = setup();
+    let query = "SELECT name FROM users WHERE id = ?";
+    let name =
+        ok!(runtime
+            .block_on(connection.query_row(query, [1], |row| { row.try_read::<String, _>(0) })));
+    assert_eq!(name, "Alice");
+    let result =
+        runtime.block_on(connection.query_row(query, [2], |row| row.try_read::<String, _>(0)));
+    assert!(result.is_err());
+}
+
+#[test]
+fn transaction() {
+    let (runtime, connection) = setup();
+    let value = ok!(runtime.block_on(connection.transaction(|transaction| {
+        transaction.execute("INSERT INTO users VALUES (2, 'Bob')")?;
+        Ok(42)
+    })));
+    assert_eq!(value, 42);
+    assert_eq!(count(&runtime, &connection), 2);
+
+    let result = runtime.block_on(connection.transaction(|transaction| {
+        transaction.execute("INSERT INTO users VALUES (3, 'Carol')")?;
+        transaction.execute(":)")
+    }));
+    assert!(result.is_err());
+    assert_eq!(count(&runtime, &connection), 2);
+}
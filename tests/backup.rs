@@ -0,0 +1,61 @@
+extern crate sqlite;
+extern crate temporary;
+
+use sqlite::Connection;
+use temporary::Directory;
+
+mod common;
+
+use common::setup_english;
+
+macro_rules! ok(($result:expr) => ($result.unwrap()));
+
+fn read(connection: &Connection) -> Vec<String> {
+    ok!(
+        ok!(connection.prepare("SELECT value FROM english ORDER BY value"))
+            .into_iter()
+            .map(|row| row.map(|row| row.read::<&str, _>(0).to_string()))
+            .collect::<sqlite::Result<Vec<_>>>()
+    )
+}
+
+#[test]
+fn backup_init() {
+    let source = setup_english(":memory:");
+    ok!(source.execute("INSERT INTO english SELECT value || value FROM english"));
+    ok!(source.execute("INSERT INTO english SELECT value || value FROM english"));
+    let mut destination = ok!(Connection::open(":memory:"));
+    {
+        let mut backup = ok!(source.backup_init(&mut destination));
+        let status = ok!(backup.step(1));
+        assert!(!status.done);
+        assert_eq!(status.remaining + 1, status.total);
+        loop {
+            let status = ok!(backup.step(1));
+            if status.done {
+                assert_eq!(status.remaining, 0);
+                break;
+            }
+        }
+        ok!(backup.finish());
+    }
+    assert_eq!(read(&destination), read(&source));
+}
+
+#[test]
+fn backup_to() {
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+
+    let source = setup_english(":memory:");
+    {
+        let mut destination = ok!(Connection::open(&path));
+        ok!(source.backup_to(&mut destination, "main"));
+    }
+    let destination = ok!(Connection::open(&path));
+    assert_eq!(read(&destination), read(&source));
+    assert_eq!(read(&destination).len(), 7);
+
+    let mut destination = ok!(Connection::open(":memory:"));
+    assert!(source.backup_to(&mut destination, "missing").is_err());
+}
@@ -0,0 +1,55 @@
+extern crate sqlite;
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+mod common;
+
+use common::setup_users;
+
+macro_rules! ok(($result:expr) => ($result.unwrap()));
+
+#[test]
+fn read() {
+    let connection = setup_users(":memory:");
+    ok!(connection.execute("INSERT INTO users VALUES (2, 'Bob', NULL, X'010203', NULL)"));
+
+    let mut blob = ok!(connection.open_blob("users", "photo", 1, false));
+    assert_eq!(blob.len(), 2);
+    let mut data = vec![];
+    ok!(blob.read_to_end(&mut data));
+    assert_eq!(data, vec![0x42, 0x69]);
+
+    ok!(blob.reopen(2));
+    assert_eq!(blob.len(), 3);
+    let mut data = [0; 2];
+    ok!(blob.read_exact(&mut data));
+    assert_eq!(data, [1, 2]);
+    assert_eq!(ok!(blob.seek(SeekFrom::End(-3))), 0);
+    let mut data = vec![];
+    ok!(blob.read_to_end(&mut data));
+    assert_eq!(data, vec![1, 2, 3]);
+
+    assert!(blob.seek(SeekFrom::Current(1)).is_err());
+    ok!(blob.seek(SeekFrom::Start(0)));
+    assert!(blob.write(&[0]).is_err());
+    assert!(connection.open_blob("users", "photo", 3, false).is_err());
+}
+
+#[test]
+fn write() {
+    let connection = setup_users(":memory:");
+    ok!(connection.execute("UPDATE users SET photo = zeroblob(4)"));
+    {
+        let mut blob = ok!(connection.open_blob("users", "photo", 1, true));
+        ok!(blob.write_all(&[1, 2]));
+        ok!(blob.seek(SeekFrom::Start(3)));
+        ok!(blob.write_all(&[4]));
+        assert!(blob.write_all(&[5]).is_err());
+    }
+    let data = ok!(
+        connection.query_row("SELECT photo FROM users", (), |row| row
+            .try_read::<&[u8], _>(0)
+            .map(<[u8]>::to_vec))
+    );
+    assert_eq!(data, vec![1, 2, 0, 4]);
+}
@@ -1,7 +1,13 @@
+#[cfg(feature = "serde")]
+extern crate serde;
 extern crate sqlite;
 extern crate temporary;
 
-use sqlite::{Connection, OpenFlags, State};
+use sqlite::{
+    Action, AttachedDatabase, Authorization, AuthorizerAction, ColumnMetadata, Connection,
+    ConnectionBuilder, CursorWithOwnership, ForeignKeyViolation, JournalMode, Limit, OpenFlags,
+    State, StatementInfo, Synchronous, Value, WalCheckpointMode,
+};
 
 mod common;
 
@@ -9,6 +15,100 @@ use common::setup_users;
 
 macro_rules! ok(($result:expr) => ($result.unwrap()));
 
+fn count(connection: &Connection, table: &str) -> i64 {
+    let statement = format!("SELECT COUNT(*) FROM {}", table);
+    ok!(connection.query_row(statement, (), |row| row.try_read::<i64, _>(0)))
+}
+
+#[test]
+fn attach() {
+    use std::fs;
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("it's.sqlite3");
+    setup_users(&path);
+
+    let connection = ok!(Connection::open(":memory:"));
+    ok!(connection.attach(&path, "other \"users\""));
+    assert_eq!(count(&connection, "\"other \"\"users\"\"\".users"), 1);
+
+    let databases = ok!(connection.attached_databases());
+    assert_eq!(databases.len(), 2);
+    assert_eq!(
+        &databases[0],
+        &AttachedDatabase {
+            seq: 0,
+            name: "main".into(),
+            file: "".into(),
+        }
+    );
+    assert_eq!(databases[1].name, "other \"users\"");
+    assert_eq!(
+        ok!(fs::canonicalize(&databases[1].file)),
+        ok!(fs::canonicalize(&path))
+    );
+
+    ok!(connection.detach("other \"users\""));
+    assert_eq!(ok!(connection.attached_databases()).len(), 1);
+    assert!(connection.detach("other").is_err());
+}
+
+#[test]
+fn builder() {
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    assert!(Connection::builder(&path).open().is_err());
+    assert!(Connection::builder(&path).read_only().open().is_err());
+    assert!(Connection::builder(&path)
+        .read_only()
+        .create()
+        .open()
+        .is_err());
+    assert!(ConnectionBuilder::new().open().is_err());
+
+    let connection = ok!(ConnectionBuilder::new()
+        .path(&path)
+        .create()
+        .busy_timeout(100)
+        .foreign_keys(true)
+        .wal_mode()
+        .open());
+    assert!(ok!(connection.foreign_keys()));
+    assert_eq!(ok!(connection.journal_mode()), JournalMode::Wal);
+    ok!(connection.execute("CREATE TABLE users (name TEXT)"));
+
+    let connection = ok!(Connection::builder(&path).read_only().open());
+    assert!(!ok!(connection.foreign_keys()));
+    assert!(connection
+        .execute("INSERT INTO users VALUES ('Alice')")
+        .is_err());
+
+    let connection = ok!(Connection::builder("file:builder?mode=memory").uri().open());
+    assert!(connection.is_in_memory());
+    let connection = ok!(Connection::builder("builder").memory().open());
+    assert!(connection.is_in_memory());
+    let result = Connection::builder(":memory:").wal_mode().open();
+    assert!(result.is_err());
+}
+
+#[test]
+fn cache_flush() {
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let connection = setup_users(&path);
+    ok!(connection.execute("BEGIN"));
+    ok!(connection.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+    ok!(connection.cache_flush());
+    ok!(connection.execute("ROLLBACK"));
+    assert_eq!(count(&connection, "users"), 1);
+    ok!(connection.cache_flush());
+}
+
 #[test]
 fn change_count() {
     let connection = setup_users(":memory:");
@@ -28,6 +128,168 @@ fn change_count() {
     assert_eq!(connection.total_change_count(), 5);
 }
 
+#[cfg(feature = "changes64")]
+#[test]
+fn change_count64() {
+    let connection = setup_users(":memory:");
+    assert_eq!(connection.change_count64(), 1);
+    assert_eq!(connection.total_change_count64(), 1);
+
+    ok!(connection.execute("INSERT INTO users SELECT * FROM users"));
+    ok!(connection.execute("INSERT INTO users SELECT * FROM users"));
+    assert_eq!(connection.change_count64(), 2);
+    assert_eq!(connection.total_change_count64(), 4);
+    assert_eq!(
+        connection.total_change_count64(),
+        connection.total_change_count() as u64
+    );
+}
+
+#[test]
+fn column_metadata() {
+    let connection = ok!(Connection::open(":memory:"));
+    ok!(connection.execute(
+        "
+        CREATE TABLE users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL COLLATE NOCASE,
+            extra
+        );
+        ",
+    ));
+
+    assert_eq!(
+        ok!(connection.column_metadata(None, "users", "id")),
+        ColumnMetadata {
+            declared_type: "INTEGER".into(),
+            collation: "BINARY".into(),
+            not_null: false,
+            primary_key: true,
+            auto_increment: true,
+        },
+    );
+    assert_eq!(
+        ok!(connection.column_metadata(Some("main"), "users", "name")),
+        ColumnMetadata {
+            declared_type: "TEXT".into(),
+            collation: "NOCASE".into(),
+            not_null: true,
+            primary_key: false,
+            auto_increment: false,
+        },
+    );
+    assert_eq!(
+        ok!(connection.column_metadata(None, "users", "extra")).declared_type,
+        "",
+    );
+    assert!(connection
+        .column_metadata(None, "users", "unknown")
+        .is_err());
+    assert!(connection.column_metadata(None, "unknown", "id").is_err());
+    assert!(connection
+        .column_metadata(Some("unknown"), "users", "id")
+        .is_err());
+}
+
+#[test]
+fn copy_from() {
+    let connection = setup_users(":memory:");
+    let rows = (2..5).map(|id| vec![Value::Integer(id), Value::String(format!("User {}", id))]);
+    assert_eq!(ok!(connection.copy_from("users", &["id", "name"], rows)), 3);
+    assert_eq!(count(&connection, "users"), 4);
+    assert_eq!(
+        ok!(connection.query_scalar::<String, _>("SELECT name FROM users WHERE id = 4")),
+        Some("User 4".into())
+    );
+
+    ok!(connection.execute("CREATE UNIQUE INDEX users_id ON users (id)"));
+    let rows = vec![vec![Value::Integer(5)], vec![Value::Integer(1)]];
+    assert!(connection.copy_from("users", &["id"], rows).is_err());
+    let rows = vec![vec![Value::Integer(5), Value::Null]];
+    assert!(connection.copy_from("users", &["id"], rows).is_err());
+    assert!(connection.copy_from("users", &[], vec![]).is_err());
+    assert_eq!(count(&connection, "users"), 4);
+    assert!(connection.is_autocommit());
+}
+
+#[test]
+fn db_filename() {
+    use std::fs;
+    use temporary::Directory;
+
+    let connection = setup_users(":memory:");
+    assert_eq!(connection.db_filename("main"), None);
+    assert_eq!(connection.db_filename("temp"), None);
+    assert!(connection.is_in_memory());
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let connection = setup_users(&path);
+    assert_eq!(
+        ok!(fs::canonicalize(connection.db_filename("main").unwrap())),
+        ok!(fs::canonicalize(&path))
+    );
+    assert_eq!(connection.db_filename("missing"), None);
+    assert!(!connection.is_in_memory());
+}
+
+#[test]
+fn db_status() {
+    use sqlite::DbStatus;
+
+    let connection = setup_users(":memory:");
+    assert_eq!(count(&connection, "users"), 1);
+    for status in [
+        DbStatus::LookasideUsed,
+        DbStatus::CacheUsed,
+        DbStatus::SchemaUsed,
+        DbStatus::StmtUsed,
+        DbStatus::LookasideHit,
+        DbStatus::LookasideMissSize,
+        DbStatus::LookasideMissFull,
+        DbStatus::CacheHit,
+        DbStatus::CacheMiss,
+        DbStatus::CacheWrite,
+        DbStatus::DeferredFks,
+        DbStatus::CacheUsedShared,
+    ] {
+        let (current, highest) = ok!(connection.db_status(status, false));
+        assert!(current >= 0);
+        assert!(highest >= 0);
+    }
+    assert!(ok!(connection.db_status(DbStatus::CacheUsed, false)).0 > 0);
+    assert!(ok!(connection.db_status(DbStatus::SchemaUsed, false)).0 > 0);
+
+    assert!(ok!(connection.db_status(DbStatus::CacheHit, true)).0 > 0);
+    assert_eq!(ok!(connection.db_status(DbStatus::CacheHit, false)).0, 0);
+}
+
+#[test]
+fn debug() {
+    use temporary::Directory;
+
+    let connection = setup_users(":memory:");
+    assert_eq!(
+        format!("{:?}", connection),
+        "Connection { path: \":memory:\" }"
+    );
+    let statement = ok!(connection.prepare("SELECT name FROM users"));
+    assert_eq!(
+        format!("{:?}", statement),
+        "Statement { sql: Some(\"SELECT name FROM users\") }"
+    );
+    let cursor = statement.into_iter();
+    assert_eq!(
+        format!("{:?}", cursor),
+        "CursorWithOwnership { statement: Statement { sql: Some(\"SELECT name FROM users\") } }"
+    );
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let connection = ok!(Connection::open_with_full_mutex(&path));
+    assert!(format!("{:?}", connection).contains("database.sqlite3"));
+}
+
 #[test]
 fn execute() {
     let connection = setup_users(":memory:");
@@ -40,6 +302,301 @@ fn execute() {
     }
 }
 
+#[test]
+fn execute_batch() {
+    let connection = setup_users(":memory:");
+    let counts = ok!(connection.execute_batch(
+        "
+        INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL);
+        -- a comment
+        SELECT * FROM users;
+        INSERT INTO users SELECT id + 2, name, age, photo, email FROM users;
+        UPDATE users SET age = 0 WHERE id > 2;
+        DELETE FROM users
+        ",
+    ));
+    assert_eq!(counts, vec![1, 0, 2, 2, 4]);
+
+    match connection.execute_batch("SELECT 1; SELECT * FROM missing; SELECT 2") {
+        Err(error) => assert_eq!(
+            error.message,
+            Some(String::from("statement 1: no such table: missing"))
+        ),
+        _ => unreachable!(),
+    }
+    assert_eq!(ok!(connection.execute_batch("")), Vec::<usize>::new());
+}
+
+#[test]
+fn execute_with_extended_code() {
+    let connection = setup_users(":memory:");
+    ok!(connection.execute("CREATE UNIQUE INDEX users_id ON users (id)"));
+    match connection.execute("INSERT INTO users VALUES (1, 'Bob', NULL, NULL, NULL)") {
+        Err(error) => {
+            assert_eq!(error.code, Some(19));
+            assert_eq!(error.extended_code(), Some(2067));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn execute_with_params() {
+    let connection = setup_users(":memory:");
+    let query = "INSERT INTO users (id, name) VALUES (?, ?)";
+    let values = &[Value::Integer(2), "Bob".into()][..];
+    assert_eq!(ok!(connection.execute_with_params(query, values)), 1);
+    let query = "UPDATE users SET age = :age WHERE id > :id";
+    let values = [(":age", 18), (":id", 0)];
+    assert_eq!(ok!(connection.execute_with_params(query, values)), 2);
+    let query = "DELETE FROM users WHERE name = ?";
+    assert_eq!(ok!(connection.execute_with_params(query, ["Carol"])), 0);
+    assert_eq!(count(&connection, "users"), 2);
+    assert!(connection.execute_with_params(":)", ()).is_err());
+}
+
+#[test]
+fn explain_query_plan() {
+    let connection = setup_users(":memory:");
+    let plan = ok!(connection.explain_query_plan("SELECT * FROM users WHERE id = 1"));
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].parent_id, 0);
+    assert!(plan[0].detail.starts_with("SCAN "));
+    assert!(plan[0].detail.contains("users"));
+
+    ok!(connection.execute("CREATE INDEX users_id ON users (id)"));
+    let plan = ok!(connection.explain_query_plan("SELECT * FROM users WHERE id = 1"));
+    assert_eq!(plan.len(), 1);
+    assert!(plan[0].detail.starts_with("SEARCH "));
+    assert!(plan[0].detail.contains("USING INDEX users_id"));
+
+    let query = "
+        SELECT * FROM users WHERE id IN (SELECT id FROM users WHERE age > 18)
+        UNION ALL
+        SELECT * FROM users
+    ";
+    let plan = ok!(connection.explain_query_plan(query));
+    let roots = plan
+        .iter()
+        .filter(|node| node.parent_id == 0)
+        .collect::<Vec<_>>();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0].detail, "COMPOUND QUERY");
+    assert!(roots[0].children(&plan).count() >= 2);
+
+    assert!(connection
+        .explain_query_plan("SELECT * FROM missing")
+        .is_err());
+}
+
+#[test]
+fn foreign_key_check() {
+    let connection = setup_users(":memory:");
+    assert!(!ok!(connection.foreign_keys()));
+    ok!(connection.execute(
+        "
+        CREATE TABLE \"user photos\" (user INTEGER REFERENCES users (id), data BLOB);
+        CREATE UNIQUE INDEX users_id ON users (id);
+        INSERT INTO \"user photos\" VALUES (1, NULL), (2, NULL), (3, NULL);
+        ",
+    ));
+    assert_eq!(
+        ok!(connection.foreign_key_check(None)),
+        vec![
+            ForeignKeyViolation {
+                table: "user photos".into(),
+                rowid: Some(2),
+                parent: "users".into(),
+                fkid: 0,
+            },
+            ForeignKeyViolation {
+                table: "user photos".into(),
+                rowid: Some(3),
+                parent: "users".into(),
+                fkid: 0,
+            },
+        ]
+    );
+    assert!(ok!(connection.foreign_key_check(Some("users"))).is_empty());
+    assert_eq!(
+        ok!(connection.foreign_key_check(Some("user photos"))).len(),
+        2
+    );
+
+    ok!(connection.enable_foreign_keys(true));
+    assert!(ok!(connection.foreign_keys()));
+    assert!(connection
+        .execute("INSERT INTO \"user photos\" VALUES (4, NULL)")
+        .is_err());
+    ok!(connection.enable_foreign_keys(false));
+    ok!(connection.execute("INSERT INTO \"user photos\" VALUES (4, NULL)"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn insert_batch() {
+    use std::collections::HashMap;
+
+    #[derive(serde::Serialize)]
+    struct User<'l> {
+        id: u32,
+        name: &'l str,
+        age: Option<f64>,
+        #[serde(with = "bytes")]
+        photo: Vec<u8>,
+        email: Option<String>,
+    }
+
+    mod bytes {
+        pub fn serialize<S: serde::Serializer>(
+            value: &[u8],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(value)
+        }
+    }
+
+    let connection = setup_users(":memory:");
+    let users = [
+        User {
+            id: 2,
+            name: "Bob",
+            age: Some(69.0),
+            photo: vec![0x69],
+            email: Some("bob@example.com".into()),
+        },
+        User {
+            id: 3,
+            name: "Carol",
+            age: None,
+            photo: vec![],
+            email: None,
+        },
+    ];
+    assert_eq!(ok!(connection.insert_batch("users", &users)), 2);
+    assert_eq!(ok!(connection.insert_batch::<User>("users", &[])), 0);
+    assert_eq!(count(&connection, "users"), 3);
+
+    let query = "SELECT * FROM users WHERE id = 2";
+    let row = ok!(ok!(connection.query(query, ())).exactly_one());
+    assert_eq!(row.read::<&str, _>("name"), "Bob");
+    assert_eq!(row.read::<f64, _>("age"), 69.0);
+    assert_eq!(row.read::<&[u8], _>("photo"), &[0x69]);
+    assert_eq!(row.read::<&str, _>("email"), "bob@example.com");
+    let query = "SELECT age IS NULL AND email IS NULL FROM users WHERE id = 3";
+    assert_eq!(
+        ok!(connection.query_row(query, (), |row| row.try_read::<i64, _>(0))),
+        1
+    );
+
+    let rows = [HashMap::from([("id", 4)]), HashMap::from([("name", 5)])];
+    assert!(connection.insert_batch("users", &rows).is_err());
+    assert!(connection.insert_batch("users", &[42]).is_err());
+    assert!(connection
+        .insert_batch("users", &[HashMap::from([("id", vec![1])])])
+        .is_err());
+    assert_eq!(count(&connection, "users"), 3);
+}
+
+#[test]
+fn integrity_check() {
+    use std::fs::{self, OpenOptions};
+    use std::io::{Seek, SeekFrom, Write};
+    use temporary::Directory;
+
+    let connection = setup_users(":memory:");
+    assert!(ok!(connection.integrity_check()).is_empty());
+    assert!(ok!(connection.quick_check()).is_empty());
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    {
+        let connection = setup_users(&path);
+        ok!(connection.execute("CREATE INDEX users_name ON users (name)"));
+        assert!(ok!(connection.integrity_check()).is_empty());
+    }
+    {
+        let data = ok!(fs::read(&path));
+        let offset = data.windows(5).position(|window| window == b"Alice");
+        let mut file = ok!(OpenOptions::new().write(true).open(&path));
+        ok!(file.seek(SeekFrom::Start(offset.unwrap() as u64)));
+        ok!(file.write_all(b"Alicf"));
+    }
+    let connection = ok!(Connection::open(&path));
+    assert!(ok!(connection.quick_check()).is_empty());
+    assert!(!ok!(connection.integrity_check()).is_empty());
+}
+
+#[test]
+fn interrupt() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    let connection = Arc::new(ok!(Connection::open_with_full_mutex(":memory:")));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let guard = {
+        let connection = connection.clone();
+        let done = done.clone();
+        thread::spawn(move || {
+            let query = "
+                WITH RECURSIVE numbers(value) AS (
+                    SELECT 1 UNION ALL SELECT value + 1 FROM numbers
+                )
+                SELECT COUNT(*) FROM numbers
+            ";
+            let result = connection.execute(query);
+            done.store(true, Ordering::SeqCst);
+            result
+        })
+    };
+    while !done.load(Ordering::SeqCst) {
+        connection.interrupt();
+        thread::sleep(Duration::from_millis(10));
+    }
+    match ok!(guard.join()) {
+        Err(error) => assert_eq!(error.code, Some(9)),
+        _ => unreachable!(),
+    }
+    ok!(connection.execute("SELECT 1"));
+}
+
+#[test]
+fn is_autocommit() {
+    let mut connection = setup_users(":memory:");
+    assert!(connection.is_autocommit());
+    ok!(connection.execute("BEGIN"));
+    assert!(!connection.is_autocommit());
+    ok!(connection.execute("COMMIT"));
+    assert!(connection.is_autocommit());
+    {
+        let transaction = ok!(connection.transaction());
+        assert!(!transaction.is_autocommit());
+    }
+    assert!(connection.is_autocommit());
+}
+
+#[test]
+fn is_readonly() {
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let connection = setup_users(&path);
+    assert!(!ok!(connection.is_readonly("main")));
+    assert!(connection.is_readonly("missing").is_err());
+
+    let connection = ok!(Connection::open_with_flags(
+        format!("file:{}?mode=ro", path.display()),
+        OpenFlags::new().set_read_write().set_uri(),
+    ));
+    assert!(ok!(connection.is_readonly("main")));
+    ok!(connection.attach(":memory:", "other"));
+    assert!(!ok!(connection.is_readonly("other")));
+}
+
 #[test]
 fn iterate() {
     macro_rules! pair(
@@ -63,6 +620,87 @@ fn iterate() {
     assert!(done);
 }
 
+#[test]
+fn last_insert_rowid() {
+    let connection = ok!(sqlite::open(":memory:"));
+    assert_eq!(connection.last_insert_rowid(), 0);
+
+    ok!(connection.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)"));
+    ok!(connection.execute("INSERT INTO users (id, name) VALUES (42, 'Alice')"));
+    assert_eq!(connection.last_insert_rowid(), 42);
+
+    ok!(connection.execute("INSERT INTO users (name) VALUES ('Bob')"));
+    assert_eq!(connection.last_insert_rowid(), 43);
+}
+
+#[cfg(feature = "loadable-extension")]
+#[test]
+fn load_extension() {
+    let connection = ok!(sqlite::open(":memory:"));
+    ok!(connection.enable_load_extension(false));
+    let error = connection.load_extension("missing", None).unwrap_err();
+    assert_eq!(error.code, Some(1));
+    assert!(ok!(error.message.as_ref()).contains("not authorized"));
+
+    ok!(connection.enable_load_extension(true));
+    let error = connection
+        .load_extension("missing", Some("sqlite3_missing_init"))
+        .unwrap_err();
+    assert_eq!(error.code, Some(1));
+    assert!(!ok!(error.message.as_ref()).contains("not authorized"));
+
+    ok!(connection.enable_load_extension(false));
+    let error = connection.load_extension("missing", None).unwrap_err();
+    assert!(ok!(error.message.as_ref()).contains("not authorized"));
+}
+
+#[test]
+fn memory_used() {
+    let _connection = setup_users(":memory:");
+    assert!(Connection::memory_used() > 0);
+    assert!(Connection::memory_highwater(false) > 0);
+}
+
+#[test]
+fn open_memory() {
+    let one = ok!(Connection::open_memory(Some("open_memory")));
+    let two = ok!(Connection::open_memory(Some("open_memory")));
+    ok!(one.execute("CREATE TABLE users (name TEXT)"));
+    ok!(one.execute("INSERT INTO users VALUES ('Alice')"));
+    let mut statement = ok!(two.prepare("SELECT name FROM users"));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<String, _>(0)), "Alice");
+
+    let three = ok!(Connection::open_memory(None));
+    assert!(three.prepare("SELECT name FROM users").is_err());
+
+    let four = ok!(Connection::open_memory(Some("open_memory?mode=rwc#%")));
+    let five = ok!(Connection::open_memory(Some("open_memory?mode=rwc#%")));
+    assert!(four.prepare("SELECT name FROM users").is_err());
+    ok!(four.execute("CREATE TABLE users (name TEXT)"));
+    ok!(five.execute("SELECT name FROM users"));
+}
+
+#[test]
+fn open_shared_cache() {
+    let path = "file:open_shared_cache?mode=memory";
+    let one = ok!(Connection::open_shared_cache(path));
+    let two = ok!(Connection::open_shared_cache(path));
+    ok!(one.execute("CREATE TABLE users (name TEXT)"));
+    ok!(one.execute("INSERT INTO users VALUES ('Alice')"));
+    assert_eq!(count(&two, "users"), 1);
+
+    ok!(one.execute("BEGIN EXCLUSIVE"));
+    ok!(one.execute("INSERT INTO users VALUES ('Bob')"));
+    let error = two.execute("SELECT * FROM users").unwrap_err();
+    assert_eq!(error.code, Some(6));
+    ok!(one.execute("COMMIT"));
+    assert_eq!(count(&two, "users"), 2);
+
+    let three = ok!(Connection::open_shared_cache("file:other?mode=memory"));
+    assert!(three.execute("SELECT * FROM users").is_err());
+}
+
 #[test]
 fn open_with_flags() {
     use temporary::Directory;
@@ -79,6 +717,74 @@ fn open_with_flags() {
     }
 }
 
+#[test]
+fn open_with_flags_exrescode() {
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    setup_users(&path);
+
+    let flags = OpenFlags::new().set_read_write().set_exrescode();
+    let connection = ok!(Connection::open_with_flags(&path, flags));
+    ok!(connection.execute("CREATE UNIQUE INDEX users_id ON users (id)"));
+    let error = connection
+        .execute("INSERT INTO users VALUES (1, 'Bob', NULL, NULL, NULL)")
+        .unwrap_err();
+    assert_eq!(error.code, Some(2067));
+    assert_eq!(error.extended_code(), Some(2067));
+}
+
+#[test]
+fn open_with_flags_memory() {
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let flags = OpenFlags::new().set_memory().set_read_write().set_create();
+    let connection = ok!(Connection::open_with_flags(&path, flags));
+    ok!(connection.execute("CREATE TABLE users (name TEXT)"));
+    assert!(connection.is_in_memory());
+    assert!(!path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn open_with_flags_nofollow() {
+    use std::os::unix::fs::symlink;
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let link = directory.path().join("link.sqlite3");
+    setup_users(&path);
+    ok!(symlink(&path, &link));
+
+    let flags = OpenFlags::new().set_read_write().set_nofollow();
+    ok!(Connection::open_with_flags(&path, flags));
+    assert!(Connection::open_with_flags(&link, flags).is_err());
+    ok!(Connection::open_with_flags(
+        &link,
+        OpenFlags::new().set_read_write()
+    ));
+}
+
+#[test]
+fn open_with_flags_uri() {
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    setup_users(&path);
+
+    let path = format!("file:{}?mode=ro", path.display());
+    let flags = OpenFlags::new().set_read_write().set_uri();
+    let connection = ok!(Connection::open_with_flags(path, flags));
+    assert!(connection
+        .execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)")
+        .is_err());
+}
+
 #[test]
 fn open_with_full_mutex() {
     use std::sync::Arc;
@@ -100,6 +806,307 @@ fn open_with_full_mutex() {
     }
 }
 
+#[test]
+fn open_with_vfs() {
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let flags = OpenFlags::new().set_create().set_read_write();
+    let vfs = ok!(sqlite::get_default_vfs().ok_or("no VFS"));
+    let connection = ok!(Connection::open_with_vfs(&path, flags, &vfs));
+    ok!(connection.execute("CREATE TABLE users (name TEXT)"));
+    assert!(Connection::open_with_vfs(&path, flags, "unknown").is_err());
+}
+
+#[test]
+fn pragma() {
+    let connection = setup_users(":memory:");
+    ok!(connection.pragma("main.cache_size", 42i64));
+    assert_eq!(ok!(connection.pragma_value::<i64>("cache_size")), Some(42));
+    ok!(connection.pragma("application_id", 69.0));
+    assert_eq!(
+        ok!(connection.pragma_value::<i64>("application_id")),
+        Some(69)
+    );
+    assert!(connection.pragma("application_id", f64::NAN).is_err());
+    assert!(connection.pragma("application_id", f64::INFINITY).is_err());
+    ok!(connection.pragma("encoding", "UTF-8"));
+    assert_eq!(
+        ok!(connection.pragma_value::<String>("encoding")),
+        Some("UTF-8".into())
+    );
+    assert_eq!(
+        ok!(connection.pragma_value::<Value>("table_info(users)")),
+        Some(Value::Integer(0))
+    );
+    assert_eq!(
+        ok!(connection.pragma_value::<Value>("table_info(none)")),
+        None
+    );
+    assert!(connection
+        .pragma("cache_size", "'; DROP TABLE users; --")
+        .is_ok());
+    assert!(ok!(connection.table_exists("users")));
+
+    assert_eq!(ok!(connection.journal_mode()), JournalMode::Memory);
+    assert_eq!(ok!(connection.synchronous()), Synchronous::Full);
+    ok!(connection.set_synchronous(Synchronous::Off));
+    assert_eq!(ok!(connection.synchronous()), Synchronous::Off);
+}
+
+#[test]
+fn prepare_cached() {
+    let mut connection = setup_users(":memory:");
+    let first = "SELECT name FROM users WHERE id = ?";
+    let second = "SELECT age FROM users WHERE id = ?";
+
+    let raw = {
+        let mut statement = ok!(connection.prepare_cached(first));
+        ok!(statement.bind((1, 1)));
+        assert_eq!(ok!(statement.next()), State::Row);
+        assert_eq!(ok!(statement.read::<String, _>(0)), "Alice");
+        statement.as_raw()
+    };
+    {
+        let mut statement = ok!(connection.prepare_cached(first));
+        assert_eq!(statement.as_raw(), raw);
+        assert_eq!(
+            statement.expanded_sql().unwrap(),
+            first.replace('?', "NULL")
+        );
+        assert_eq!(ok!(statement.next()), State::Done);
+        let other = ok!(connection.prepare_cached(first));
+        assert_ne!(other.as_raw(), raw);
+    }
+
+    connection.set_statement_cache_capacity(0);
+    let mut statement = ok!(connection.prepare_cached(second));
+    ok!(statement.bind((1, 1)));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<f64, _>(0)), 42.69);
+    assert!(connection.prepare_cached("SELECT * FROM missing").is_err());
+}
+
+#[test]
+fn prepared_statements() {
+    let mut connection = setup_users(":memory:");
+    connection.set_statement_cache_capacity(0);
+    assert_eq!(connection.prepared_statements(), vec![]);
+
+    let mut one = ok!(connection.prepare("SELECT * FROM users"));
+    let two = ok!(connection.prepare("SELECT name FROM users"));
+    assert_eq!(ok!(one.next()), State::Row);
+    let mut statements = connection.prepared_statements();
+    statements.sort_by(|one, two| one.sql.cmp(&two.sql));
+    assert_eq!(
+        statements,
+        vec![
+            StatementInfo {
+                sql: "SELECT * FROM users".into(),
+                is_busy: true,
+            },
+            StatementInfo {
+                sql: "SELECT name FROM users".into(),
+                is_busy: false,
+            },
+        ],
+    );
+
+    ok!(one.reset());
+    assert!(connection
+        .prepared_statements()
+        .iter()
+        .all(|statement| !statement.is_busy));
+    drop(one);
+    drop(two);
+    assert_eq!(connection.prepared_statements(), vec![]);
+}
+
+#[test]
+fn query() {
+    fn users(connection: &Connection, id: i64) -> sqlite::Result<CursorWithOwnership<'_>> {
+        connection.query("SELECT name FROM users WHERE id >= ? ORDER BY id", [id])
+    }
+
+    let connection = setup_users(":memory:");
+    ok!(connection.execute("INSERT INTO users (id, name) VALUES (2, 'Bob')"));
+    let names = ok!(users(&connection, 1))
+        .map(|row| ok!(row).read::<String, _>(0))
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Alice", "Bob"]);
+    assert_eq!(ok!(users(&connection, 3)).count(), 0);
+    assert!(connection.query("SELECT * FROM users", [1]).is_err());
+}
+
+#[test]
+fn query_map() {
+    let connection = setup_users(":memory:");
+    ok!(connection.execute("INSERT INTO users (id, name) VALUES (2, 'Bob')"));
+    let query = "SELECT id, name FROM users WHERE id > ? ORDER BY id";
+    let mut calls = 0;
+    let users = ok!(connection.query_map(query, [0], |row| {
+        calls += 1;
+        Ok((row.try_get::<i64, _>(0)?, row.try_get::<String, _>(1)?))
+    }));
+    let users = ok!(users.collect::<sqlite::Result<Vec<_>>>());
+    assert_eq!(users, vec![(1, "Alice".into()), (2, "Bob".into())]);
+    assert_eq!(calls, 2);
+
+    let mut names = ok!(connection.query_map(query, [0], |row| row.try_get::<i64, _>(1)));
+    assert!(names.next().unwrap().is_err());
+}
+
+#[test]
+fn query_row() {
+    let connection = setup_users(":memory:");
+    ok!(connection.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+
+    let query = "SELECT COUNT(*) FROM users";
+    let count = ok!(connection.query_row(query, (), |row| row.try_read::<i64, _>(0)));
+    assert_eq!(count, 2);
+
+    let query = "SELECT name FROM users WHERE id = ?";
+    let name = ok!(connection.query_row(query, [2], |row| {
+        row.try_read::<&str, _>("name").map(String::from)
+    }));
+    assert_eq!(name, "Bob");
+
+    let query = "SELECT name FROM users WHERE id = :id";
+    match connection.query_row(query, [(":id", 3)], |_| Ok(())) {
+        Err(error) => assert_eq!(
+            error.message,
+            Some(String::from("the statement returned no rows"))
+        ),
+        _ => unreachable!(),
+    }
+
+    let query = "SELECT name FROM users";
+    match connection.query_row(query, (), |_| Ok(())) {
+        Err(error) => assert_eq!(
+            error.message,
+            Some(String::from("the statement returned more than one row"))
+        ),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn query_scalar() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT COUNT(*) FROM users";
+    assert_eq!(ok!(connection.query_scalar::<i64, _>(query)), Some(1));
+    let query = "SELECT MAX(age) FROM users";
+    assert_eq!(ok!(connection.query_scalar::<f64, _>(query)), Some(42.69));
+    let query = "SELECT name FROM users WHERE id = 2";
+    assert_eq!(ok!(connection.query_scalar::<String, _>(query)), None);
+    let query = "SELECT NULL";
+    assert_eq!(ok!(connection.query_scalar::<Value, _>(query)), None);
+    assert!(connection
+        .query_scalar::<i64, _>("SELECT * FROM missing")
+        .is_err());
+}
+
+#[test]
+fn recursive_triggers() {
+    let connection = ok!(Connection::open(":memory:"));
+    ok!(connection.execute(
+        "
+        CREATE TABLE numbers (value INTEGER);
+        CREATE TRIGGER next AFTER INSERT ON numbers WHEN new.value < 5 BEGIN
+            INSERT INTO numbers VALUES (new.value + 1);
+        END;
+        ",
+    ));
+    assert!(!ok!(connection.recursive_triggers()));
+    ok!(connection.execute("INSERT INTO numbers VALUES (1)"));
+    assert_eq!(count(&connection, "numbers"), 2);
+
+    ok!(connection.set_recursive_triggers(true));
+    assert!(ok!(connection.recursive_triggers()));
+    ok!(connection.execute("DELETE FROM numbers"));
+    ok!(connection.execute("INSERT INTO numbers VALUES (1)"));
+    assert_eq!(count(&connection, "numbers"), 5);
+
+    ok!(connection.set_recursive_triggers(false));
+    assert!(!ok!(connection.recursive_triggers()));
+}
+
+#[test]
+fn release_memory() {
+    let connection = setup_users(":memory:");
+    assert_eq!(count(&connection, "users"), 1);
+    assert!(connection.release_memory() >= 0);
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn serialize() {
+    let source = setup_users(":memory:");
+    ok!(source.execute(
+        "
+        CREATE TABLE animals (name TEXT);
+        INSERT INTO animals VALUES ('cat'), ('dog');
+        INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL);
+        ",
+    ));
+    let data = ok!(source.serialize(None));
+    assert!(!data.is_empty());
+
+    let mut destination = ok!(Connection::open(":memory:"));
+    ok!(destination.deserialize(None, data));
+    assert_eq!(count(&destination, "animals"), 2);
+    assert_eq!(count(&destination, "users"), 2);
+
+    ok!(destination.execute("INSERT INTO animals VALUES ('fox')"));
+    assert_eq!(count(&destination, "animals"), 3);
+    assert_eq!(ok!(destination.serialize(Some("main"))).len() % 512, 0);
+    assert!(destination.serialize(Some("missing")).is_err());
+}
+
+#[test]
+fn set_authorizer() {
+    use std::sync::{Arc, Mutex};
+
+    let mut connection = setup_users(":memory:");
+    ok!(connection.execute("CREATE TABLE secrets (value TEXT)"));
+    ok!(connection.execute("UPDATE users SET email = 'alice@example.com'"));
+    let actions = Arc::new(Mutex::new(vec![]));
+    let actions_clone = actions.clone();
+    ok!(connection.set_authorizer(move |action| {
+        let decision = match action {
+            AuthorizerAction::ReadColumn {
+                table: "secrets", ..
+            } => Authorization::Deny,
+            AuthorizerAction::ReadColumn {
+                column: "email", ..
+            } => Authorization::Ignore,
+            _ => Authorization::Ok,
+        };
+        if let AuthorizerAction::Insert { table } = action {
+            ok!(actions_clone.lock()).push(table.to_string());
+        }
+        decision
+    }));
+
+    let error = connection.prepare("SELECT * FROM secrets").err().unwrap();
+    assert_eq!(error.code, Some(23));
+    let email = ok!(connection.query_row("SELECT email FROM users", (), |row| {
+        row.try_read::<Option<&str>, _>(0)
+            .map(|email| email.is_none())
+    }));
+    assert!(email);
+    ok!(connection.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+    assert_eq!(*ok!(actions.lock()), vec!["users".to_string()]);
+
+    ok!(connection.set_authorizer(|_| panic!("authorizer")));
+    let error = connection.prepare("SELECT * FROM secrets").err().unwrap();
+    assert_eq!(error.code, Some(23));
+
+    ok!(connection.remove_authorizer());
+    ok!(connection.execute("SELECT * FROM secrets"));
+}
+
 #[test]
 fn set_busy_handler() {
     use std::thread;
@@ -132,3 +1139,481 @@ fn set_busy_handler() {
         assert!(ok!(guard.join()));
     }
 }
+
+#[test]
+fn set_commit_hook() {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let mut connection = setup_users(":memory:");
+    let allow = Arc::new(AtomicBool::new(true));
+    let commits = Arc::new(AtomicUsize::new(0));
+    let rollbacks = Arc::new(AtomicUsize::new(0));
+    {
+        let allow = allow.clone();
+        let commits = commits.clone();
+        connection.set_commit_hook(move || {
+            commits.fetch_add(1, Ordering::SeqCst);
+            allow.load(Ordering::SeqCst)
+        });
+    }
+    {
+        let rollbacks = rollbacks.clone();
+        connection.set_rollback_hook(move || {
+            rollbacks.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+
+    ok!(connection.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+    assert_eq!(commits.load(Ordering::SeqCst), 1);
+    assert_eq!(rollbacks.load(Ordering::SeqCst), 0);
+
+    allow.store(false, Ordering::SeqCst);
+    ok!(connection.execute("BEGIN"));
+    ok!(connection.execute("DELETE FROM users"));
+    assert!(connection.execute("COMMIT").is_err());
+    assert_eq!(commits.load(Ordering::SeqCst), 2);
+    assert_eq!(rollbacks.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        ok!(
+            connection.query_row("SELECT COUNT(*) FROM users", (), |row| row
+                .try_read::<i64, _>(0))
+        ),
+        2
+    );
+
+    ok!(connection.execute("BEGIN"));
+    ok!(connection.execute("ROLLBACK"));
+    assert_eq!(rollbacks.load(Ordering::SeqCst), 2);
+
+    connection.remove_commit_hook();
+    connection.remove_rollback_hook();
+    ok!(connection.execute("DELETE FROM users"));
+    ok!(connection.execute("BEGIN"));
+    ok!(connection.execute("ROLLBACK"));
+    assert_eq!(commits.load(Ordering::SeqCst), 2);
+    assert_eq!(rollbacks.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn set_extended_result_codes() {
+    let mut connection = setup_users(":memory:");
+    ok!(connection.enable_foreign_keys(true));
+    ok!(connection.execute(
+        "
+        CREATE TABLE orders (user_id INTEGER REFERENCES users (id));
+        CREATE UNIQUE INDEX users_id ON users (id);
+        "
+    ));
+    let query = "INSERT INTO orders VALUES (42)";
+
+    let error = connection.execute(query).unwrap_err();
+    assert_eq!(error.code, Some(19));
+    assert_eq!(error.extended_code(), Some(787));
+
+    ok!(connection.set_extended_result_codes(true));
+    let error = connection.execute(query).unwrap_err();
+    assert_eq!(error.code, Some(787));
+    assert_eq!(error.code_name(), Some("SQLITE_CONSTRAINT"));
+    let error = connection
+        .execute("INSERT INTO users VALUES (1, 'Bob', NULL, NULL, NULL)")
+        .unwrap_err();
+    assert_eq!(error.code, Some(2067));
+    assert_eq!(
+        error.to_string(),
+        "UNIQUE constraint failed: users.id (code 2067, SQLITE_CONSTRAINT)",
+    );
+
+    ok!(connection.set_extended_result_codes(false));
+    let error = connection.execute(query).unwrap_err();
+    assert_eq!(error.code, Some(19));
+}
+
+#[test]
+fn set_journal_mode() {
+    use temporary::Directory;
+
+    let connection = setup_users(":memory:");
+    assert_eq!(
+        ok!(connection.set_journal_mode(JournalMode::Wal)),
+        JournalMode::Memory
+    );
+    assert_eq!(
+        ok!(connection.set_journal_mode(JournalMode::Off)),
+        JournalMode::Off
+    );
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let connection = setup_users(&path);
+    assert_eq!(
+        ok!(connection.set_journal_mode(JournalMode::Truncate)),
+        JournalMode::Truncate
+    );
+    assert_eq!(
+        ok!(connection.wal_checkpoint(WalCheckpointMode::Passive)),
+        (0, 0)
+    );
+    assert_eq!(
+        ok!(connection.set_journal_mode(JournalMode::Wal)),
+        JournalMode::Wal
+    );
+    ok!(connection.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+    let (log_count, checkpoint_count) = ok!(connection.wal_checkpoint(WalCheckpointMode::Full));
+    assert!(log_count > 0);
+    assert_eq!(log_count, checkpoint_count);
+    assert_eq!(
+        ok!(connection.wal_checkpoint(WalCheckpointMode::Truncate)),
+        (0, 0)
+    );
+}
+
+#[test]
+fn set_limit() {
+    let connection = ok!(sqlite::open(":memory:"));
+    let column = connection.get_limit(Limit::Column);
+    assert!(column > 2);
+    assert_eq!(connection.set_limit(Limit::Column, 2), column);
+    assert_eq!(connection.get_limit(Limit::Column), 2);
+    assert_eq!(connection.set_limit(Limit::Column, -1), 2);
+    assert_eq!(connection.get_limit(Limit::Column), 2);
+    ok!(connection.execute("SELECT 1, 2"));
+    assert!(connection.execute("SELECT 1, 2, 3").is_err());
+
+    let length = connection.get_limit(Limit::SqlLength);
+    assert_eq!(connection.set_limit(Limit::SqlLength, 10), length);
+    assert!(connection.execute("SELECT 1 + 1 + 1").is_err());
+}
+
+#[test]
+fn set_progress_handler() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let mut connection = ok!(sqlite::open(":memory:"));
+    let query = "
+        WITH RECURSIVE numbers(value) AS (
+            SELECT 1 UNION ALL SELECT value + 1 FROM numbers
+        )
+        SELECT COUNT(*) FROM numbers
+    ";
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls_ = calls.clone();
+    ok!(connection.set_progress_handler(100, move || calls_.fetch_add(1, Ordering::SeqCst) < 10));
+    match connection.execute(query) {
+        Err(error) => assert_eq!(error.code, Some(9)),
+        _ => unreachable!(),
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 11);
+
+    ok!(connection.remove_progress_handler());
+    assert!(connection.set_progress_handler(u32::MAX, || true).is_err());
+
+    ok!(connection.set_progress_handler(100, || panic!("progress")));
+    assert_eq!(connection.execute(query).unwrap_err().code, Some(9));
+    ok!(connection.execute("SELECT 1"));
+    assert_eq!(calls.load(Ordering::SeqCst), 11);
+}
+
+#[test]
+fn set_trace_hook() {
+    use sqlite::{TraceEvent, TraceFlags};
+    use std::sync::{Arc, Mutex};
+
+    let mut connection = setup_users(":memory:");
+    let events = Arc::new(Mutex::new(vec![]));
+    let events_ = events.clone();
+    let flags = TraceFlags::new()
+        .set_statement()
+        .set_profile()
+        .set_row()
+        .set_close();
+    ok!(connection.set_trace_hook(flags, move |event| {
+        let event = match event {
+            TraceEvent::Statement { sql } => format!("statement {}", sql),
+            TraceEvent::Profile { sql, .. } => format!("profile {}", sql),
+            TraceEvent::Row { sql } => format!("row {}", sql),
+            TraceEvent::Close => "close".to_string(),
+        };
+        ok!(events_.lock()).push(event);
+    }));
+
+    ok!(connection.execute("INSERT INTO users (id) VALUES (2); INSERT INTO users (id) VALUES (3);"));
+    let mut statement = ok!(connection.prepare("SELECT id FROM users WHERE id = ?"));
+    ok!(statement.bind((1, 3)));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.next()), State::Done);
+    drop(statement);
+    ok!(connection.remove_trace_hook());
+    ok!(connection.execute("DELETE FROM users"));
+
+    let query = "SELECT id FROM users WHERE id = ?";
+    assert_eq!(
+        *ok!(events.lock()),
+        vec![
+            "statement INSERT INTO users (id) VALUES (2);".to_string(),
+            "profile INSERT INTO users (id) VALUES (2);".to_string(),
+            "statement INSERT INTO users (id) VALUES (3);".to_string(),
+            "profile INSERT INTO users (id) VALUES (3);".to_string(),
+            format!("statement {}", query),
+            format!("row {}", query),
+            format!("profile {}", query),
+        ]
+    );
+
+    let events = Arc::new(Mutex::new(vec![]));
+    let events_ = events.clone();
+    ok!(
+        connection.set_trace_hook(TraceFlags::new().set_close(), move |event| {
+            ok!(events_.lock()).push(event == TraceEvent::Close);
+        })
+    );
+    drop(connection);
+    assert_eq!(*ok!(events.lock()), vec![true]);
+}
+
+#[test]
+fn set_update_hook() {
+    use std::sync::{Arc, Mutex};
+
+    let mut connection = setup_users(":memory:");
+    let changes = Arc::new(Mutex::new(vec![]));
+    let changes_ = changes.clone();
+    connection.set_update_hook(move |action, database, table, rowid| {
+        ok!(changes_.lock()).push((action, database.to_string(), table.to_string(), rowid));
+    });
+
+    ok!(connection.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+    ok!(connection.execute("UPDATE users SET age = 0 WHERE id = 2"));
+    ok!(connection.execute("DELETE FROM users WHERE id = 1"));
+    connection.remove_update_hook();
+    ok!(connection.execute("DELETE FROM users"));
+
+    let main = String::from("main");
+    let users = String::from("users");
+    assert_eq!(
+        *ok!(changes.lock()),
+        vec![
+            (Action::Insert, main.clone(), users.clone(), 2),
+            (Action::Update, main.clone(), users.clone(), 2),
+            (Action::Delete, main.clone(), users.clone(), 1),
+        ]
+    );
+}
+
+#[test]
+fn set_user_version() {
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    {
+        let connection = setup_users(&path);
+        assert_eq!(ok!(connection.user_version()), 0);
+        assert_eq!(ok!(connection.application_id()), 0);
+        let version = ok!(connection.user_version());
+        ok!(connection.set_user_version(version + 1));
+        ok!(connection.set_application_id(-42));
+    }
+    let connection = ok!(Connection::open(&path));
+    assert_eq!(ok!(connection.user_version()), 1);
+    assert_eq!(ok!(connection.application_id()), -42);
+}
+
+#[test]
+fn set_wal_hook() {
+    use std::sync::{Arc, Mutex};
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let mut connection = setup_users(&path);
+    ok!(connection.set_journal_mode(JournalMode::Wal));
+    ok!(connection.set_wal_autocheckpoint(0));
+
+    let commits = Arc::new(Mutex::new(vec![]));
+    let commits_ = commits.clone();
+    connection.set_wal_hook(move |database, pages| {
+        ok!(commits_.lock()).push((database.to_string(), pages));
+    });
+    ok!(connection.execute("INSERT INTO users (id) VALUES (2)"));
+    ok!(connection.execute("INSERT INTO users (id) VALUES (3)"));
+    connection.remove_wal_hook();
+    ok!(connection.execute("INSERT INTO users (id) VALUES (4)"));
+
+    let commits = ok!(commits.lock());
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].0, "main");
+    assert!(commits[0].1 > 0);
+    assert!(commits[1].1 > commits[0].1);
+
+    let (log_count, _) = ok!(connection.wal_checkpoint(WalCheckpointMode::Passive));
+    assert!(log_count > commits[1].1);
+
+    ok!(connection.set_wal_autocheckpoint(1));
+    ok!(connection.execute("INSERT INTO users (id) VALUES (5)"));
+    let (log_count, checkpoint_count) = ok!(connection.wal_checkpoint(WalCheckpointMode::Passive));
+    assert_eq!(log_count, checkpoint_count);
+}
+
+#[cfg(feature = "snapshot")]
+#[test]
+fn snapshot_get() {
+    use temporary::Directory;
+
+    if !sqlite::compile_option_used("ENABLE_SNAPSHOT") {
+        let connection = ok!(sqlite::open(":memory:"));
+        assert!(connection.snapshot_get("main").is_err());
+        return;
+    }
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let one = setup_users(&path);
+    ok!(one.set_journal_mode(JournalMode::Wal));
+    let two = ok!(Connection::open(&path));
+
+    ok!(one.execute("BEGIN"));
+    assert_eq!(count(&one, "users"), 1);
+    let snapshot = ok!(one.snapshot_get("main"));
+    ok!(one.execute("COMMIT"));
+
+    ok!(two.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+    assert_eq!(count(&one, "users"), 2);
+
+    ok!(one.execute("BEGIN"));
+    ok!(one.snapshot_open("main", &snapshot));
+    assert_eq!(count(&one, "users"), 1);
+    ok!(one.execute("COMMIT"));
+    assert_eq!(count(&one, "users"), 2);
+
+    assert!(one.snapshot_get("main").is_err());
+}
+
+#[test]
+fn soft_heap_limit() {
+    let limit = Connection::soft_heap_limit(-1);
+    assert_eq!(Connection::soft_heap_limit(1 << 40), limit);
+    assert_eq!(Connection::soft_heap_limit(-1), 1 << 40);
+    assert_eq!(Connection::soft_heap_limit(limit), 1 << 40);
+}
+
+#[test]
+fn sqlite_version() {
+    let version = Connection::sqlite_version();
+    let number = Connection::sqlite_version_number();
+    let parts = version
+        .split('.')
+        .map(|part| ok!(part.parse::<u32>()))
+        .collect::<Vec<_>>();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0], 3);
+    assert_eq!(number, parts[0] * 1_000_000 + parts[1] * 1_000 + parts[2]);
+
+    let source_id = Connection::sqlite_source_id();
+    assert!(source_id.len() > 10);
+    let query = "SELECT sqlite_version(), sqlite_source_id()";
+    let connection = ok!(sqlite::open(":memory:"));
+    let mut statement = ok!(connection.prepare(query));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<String, _>(0)), version);
+    assert_eq!(ok!(statement.read::<String, _>(1)), source_id);
+}
+
+#[test]
+fn tables() {
+    let connection = setup_users(":memory:");
+    assert!(ok!(connection.table_exists("users")));
+    assert!(!ok!(connection.table_exists("animals")));
+    assert_eq!(ok!(connection.tables()), vec!["users"]);
+    assert!(ok!(connection.views()).is_empty());
+    assert!(ok!(connection.indexes(None)).is_empty());
+
+    ok!(connection.execute(
+        "
+        CREATE TABLE \"my animals\" (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT UNIQUE);
+        CREATE INDEX users_name ON users (name);
+        CREATE INDEX users_age ON users (age);
+        CREATE INDEX animal_name ON \"my animals\" (name);
+        CREATE VIEW adults AS SELECT * FROM users WHERE age >= 18;
+        ",
+    ));
+    assert!(ok!(connection.table_exists("my animals")));
+    assert!(ok!(connection.table_exists("Users")));
+    assert!(ok!(connection.table_exists("MY ANIMALS")));
+    assert_eq!(ok!(connection.tables()), vec!["my animals", "users"]);
+    assert_eq!(ok!(connection.views()), vec!["adults"]);
+    assert_eq!(
+        ok!(connection.indexes(None)),
+        vec!["animal_name", "users_age", "users_name"]
+    );
+    assert_eq!(
+        ok!(connection.indexes(Some("users"))),
+        vec!["users_age", "users_name"]
+    );
+    assert_eq!(
+        ok!(connection.indexes(Some("Users"))),
+        vec!["users_age", "users_name"]
+    );
+
+    ok!(connection.execute("DROP TABLE \"my animals\"; DROP VIEW adults"));
+    assert!(!ok!(connection.table_exists("my animals")));
+    assert_eq!(ok!(connection.tables()), vec!["users"]);
+    assert!(ok!(connection.views()).is_empty());
+    assert_eq!(
+        ok!(connection.indexes(None)),
+        vec!["users_age", "users_name"]
+    );
+}
+
+#[test]
+fn unlock_notify() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let path = "file:unlock_notify?mode=memory";
+    let one = ok!(Connection::open_shared_cache(path));
+    let mut two = ok!(Connection::open_shared_cache(path));
+    ok!(one.execute("CREATE TABLE users (name TEXT)"));
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let counter_clone = counter.clone();
+    ok!(two.unlock_notify(move || {
+        counter_clone.fetch_add(1, Ordering::SeqCst);
+    }));
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+    ok!(one.execute("BEGIN EXCLUSIVE"));
+    ok!(one.execute("INSERT INTO users VALUES ('Alice')"));
+    assert_eq!(
+        two.execute("SELECT * FROM users").unwrap_err().code,
+        Some(6)
+    );
+    let counter_clone = counter.clone();
+    ok!(two.unlock_notify(move || {
+        counter_clone.fetch_add(1, Ordering::SeqCst);
+    }));
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+    ok!(one.execute("COMMIT"));
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+    assert_eq!(count(&two, "users"), 1);
+}
+
+#[test]
+fn vacuum_into() {
+    use temporary::Directory;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("it's.sqlite3");
+    let connection = setup_users(":memory:");
+    ok!(connection.vacuum());
+    ok!(connection.vacuum_into(&path));
+    assert!(connection.vacuum_into(&path).is_err());
+
+    let connection = ok!(Connection::open(&path));
+    assert_eq!(count(&connection, "users"), 1);
+
+    ok!(connection.execute("BEGIN"));
+    assert!(connection.vacuum().is_err());
+}
@@ -1,6 +1,9 @@
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_json;
 extern crate sqlite;
 
-use sqlite::{Type, Value};
+use sqlite::{FromSql, Type, Value};
 use std::collections::HashMap;
 
 mod common;
@@ -9,6 +12,21 @@ use common::{setup_english, setup_users};
 
 macro_rules! ok(($result:expr) => ($result.unwrap()));
 
+#[test]
+fn bind() {
+    let connection = setup_english(":memory:");
+    let query = "SELECT COUNT(*) FROM english WHERE value LIKE ?";
+    let mut statement = ok!(connection.prepare(query));
+
+    let mut cursor = ok!(statement.iter().bind((1, "%type")));
+    assert_eq!(ok!(ok!(cursor.next())).read::<i64, _>(0), 6);
+    for (pattern, count) in [("non%", 1), ("%o%", 5), ("%xyz", 0)] {
+        cursor = ok!(cursor.bind((1, pattern)));
+        assert_eq!(ok!(ok!(cursor.next())).read::<i64, _>(0), count);
+        assert!(cursor.next().is_none());
+    }
+}
+
 #[test]
 fn bind_iter() {
     let connection = ok!(sqlite::open(":memory:"));
@@ -33,6 +51,21 @@ fn column_count() {
     assert_eq!(cursor.column_count(), 1);
 }
 
+#[test]
+fn column_names() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT id, name AS username FROM users";
+    let mut statement = ok!(connection.prepare(query));
+
+    let mut cursor = statement.iter();
+    assert!(ok!(cursor.try_next()).is_some());
+    assert_eq!(cursor.column_names(), &["id", "username"]);
+    assert_eq!(ok!(cursor.column_name(1)), "username");
+
+    let cursor = ok!(connection.query(query, ()));
+    assert_eq!(cursor.column_names(), &["id", "username"]);
+}
+
 #[test]
 fn column_type() {
     let connection = setup_english(":memory:");
@@ -69,6 +102,33 @@ fn count() {
     assert_eq!(statement.iter().filter(|row| row.is_ok()).count(), 6);
 }
 
+#[test]
+fn exactly_one() {
+    let connection = setup_english(":memory:");
+    let query = "SELECT value FROM english WHERE value LIKE ?";
+    let mut statement = ok!(connection.prepare(query));
+
+    let row = ok!(ok!(statement.iter().bind((1, "non%"))).exactly_one());
+    assert_eq!(row.read::<&str, _>(0), "nonsence");
+    assert!(ok!(statement.iter().bind((1, "%type")))
+        .exactly_one()
+        .is_err());
+    assert!(ok!(statement.iter().bind((1, "%xyz")))
+        .exactly_one()
+        .is_err());
+}
+
+#[test]
+fn first() {
+    let connection = setup_english(":memory:");
+    let query = "SELECT value FROM english WHERE value LIKE ? ORDER BY value";
+    let mut statement = ok!(connection.prepare(query));
+
+    let row = ok!(ok!(statement.iter().bind((1, "%type"))).first());
+    assert_eq!(ok!(row).read::<&str, _>(0), "cerotype");
+    assert!(ok!(ok!(statement.iter().bind((1, "%xyz"))).first()).is_none());
+}
+
 #[test]
 fn iter() {
     let connection = setup_users(":memory:");
@@ -84,13 +144,44 @@ fn iter() {
         } else if id == 2 {
             assert_eq!(row.read::<Option<f64>, _>("age"), None);
         } else {
-            assert!(false);
+            unreachable!();
         }
         count += 1;
     }
     assert_eq!(count, 2);
 }
 
+#[test]
+fn map_rows() {
+    let connection = setup_english(":memory:");
+    let query = "SELECT value FROM english WHERE value LIKE ? ORDER BY value";
+    let mut statement = ok!(connection.prepare(query));
+
+    let lengths = ok!(statement.iter().bind((1, "%type")))
+        .map_rows(|row| Ok(row.try_get::<String, _>(0)?.len()))
+        .take(2)
+        .collect::<sqlite::Result<Vec<_>>>();
+    assert_eq!(ok!(lengths), vec![8, 8]);
+
+    let mut values = statement.iter().map_rows(|row| row.try_get::<i64, _>(0));
+    assert!(values.next().unwrap().is_err());
+}
+
+#[test]
+fn next_column_name() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT id, name, age AS years, id FROM users";
+    let mut statement = ok!(connection.prepare(query));
+
+    let row = ok!(ok!(statement.iter().next()));
+    assert_eq!(row.column_count(), 4);
+    assert_eq!(row.column_name(0), Some("id"));
+    assert_eq!(row.column_name(2), Some("years"));
+    assert_eq!(row.column_name(3), Some("id"));
+    assert_eq!(row.column_name(4), None);
+    assert_eq!(row.column_names(), &["id", "name", "years", "id"]);
+}
+
 #[test]
 fn next_index() {
     let connection = setup_users(":memory:");
@@ -101,9 +192,39 @@ fn next_index() {
 
     assert_eq!(row[0], Value::Integer(1));
     assert_eq!(row[2], Value::Float(42.69));
+    assert_eq!(row[2i32], Value::Float(42.69));
 
     assert_eq!(row["id"], Value::Integer(1));
     assert_eq!(row["age"], Value::Float(42.69));
+    assert_eq!(row[String::from("age")], Value::Float(42.69));
+}
+
+#[test]
+fn next_into_iter() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT id, name, email FROM users";
+    let mut statement = ok!(connection.prepare(query));
+
+    let row = ok!(ok!(statement.iter().next()));
+    let pairs = (&row).into_iter().collect::<Vec<_>>();
+    assert_eq!(
+        pairs,
+        vec![
+            ("id", &Value::Integer(1)),
+            ("name", &Value::String("Alice".into())),
+            ("email", &Value::Null),
+        ]
+    );
+
+    let pairs = row.into_iter().collect::<Vec<_>>();
+    assert_eq!(
+        pairs,
+        vec![
+            ("id".to_string(), Value::Integer(1)),
+            ("name".to_string(), Value::String("Alice".into())),
+            ("email".to_string(), Value::Null),
+        ]
+    );
 }
 
 #[test]
@@ -115,6 +236,7 @@ fn next_read_with_name() {
     let row = ok!(ok!(statement.iter().next()));
     assert_eq!(row.read::<i64, _>("id"), 1);
     assert_eq!(row.read::<&str, _>("name"), "Alice");
+    assert_eq!(row.read::<&str, _>(String::from("name")), "Alice");
     assert_eq!(row.read::<f64, _>("age"), 42.69);
     assert_eq!(row.read::<&[u8], _>("photo"), &[0x42u8, 0x69u8][..]);
 }
@@ -133,6 +255,51 @@ fn next_read_with_name_and_option() {
     assert!(row.read::<Option<&str>, _>("email").is_none());
 }
 
+#[cfg(feature = "serde")]
+#[test]
+fn next_serialize() {
+    let connection = setup_users(":memory:");
+    let mut statement = ok!(connection.prepare("SELECT * FROM users"));
+    let row = ok!(ok!(statement.iter().next()));
+    assert_eq!(
+        ok!(serde_json::to_value(&row)),
+        json!({
+            "id": 1,
+            "name": "Alice",
+            "age": 42.69,
+            "photo": [0x42, 0x69],
+            "email": null,
+        })
+    );
+    assert_eq!(ok!(serde_json::to_string(&row["name"])), "\"Alice\"");
+}
+
+#[test]
+fn next_try_get() {
+    #[derive(Debug, PartialEq)]
+    struct Age(f64);
+
+    impl FromSql for Age {
+        fn from_sql(value: &Value) -> sqlite::Result<Self> {
+            f64::from_sql(value).map(Age)
+        }
+    }
+
+    let connection = setup_users(":memory:");
+    let query = "SELECT * FROM users";
+    let mut statement = ok!(connection.prepare(query));
+
+    let row = ok!(ok!(statement.iter().next()));
+    assert_eq!(ok!(row.try_get::<i64, _>("id")), 1);
+    assert_eq!(ok!(row.try_get::<String, _>(1)), "Alice");
+    assert_eq!(ok!(row.try_get::<Age, _>("age")), Age(42.69));
+    assert_eq!(ok!(row.try_get::<Option<Age>, _>("age")), Some(Age(42.69)));
+    assert_eq!(ok!(row.try_get::<Option<Age>, _>("email")), None);
+    assert_eq!(ok!(row.try_get::<Value, _>("email")), Value::Null);
+    assert!(row.try_get::<Age, _>("name").is_err());
+    assert!(row.try_get::<String, _>("email").is_err());
+}
+
 #[test]
 fn next_try_read_with_index() {
     let connection = setup_users(":memory:");
@@ -209,6 +376,17 @@ fn try_next_try_into() {
     assert!(row[4].try_into::<&str>().is_err());
 }
 
+#[test]
+fn try_collect() {
+    let connection = setup_english(":memory:");
+    let query = "SELECT value FROM english WHERE value LIKE ?";
+    let mut statement = ok!(connection.prepare(query));
+
+    let rows = ok!(ok!(statement.iter().bind((1, "%type"))).try_collect::<Vec<_>>());
+    assert_eq!(rows.len(), 6);
+    assert_eq!(rows[0].read::<&str, _>(0), "cerotype");
+}
+
 #[test]
 fn workflow() {
     let connection = setup_users(":memory:");
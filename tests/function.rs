@@ -0,0 +1,298 @@
+extern crate sqlite;
+
+use sqlite::{Aggregate, FunctionFlags, State, Value};
+
+mod common;
+
+use common::setup_english;
+
+macro_rules! ok(($result:expr) => ($result.unwrap()));
+
+fn read(connection: &sqlite::Connection, query: &str) -> Vec<String> {
+    let mut statement = ok!(connection.prepare(query));
+    let mut values = vec![];
+    while let State::Row = ok!(statement.next()) {
+        values.push(ok!(statement.read::<String, _>(0)));
+    }
+    values
+}
+
+#[test]
+fn collation_needed() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let mut connection = setup_english(":memory:");
+    let counter = Arc::new(AtomicUsize::new(0));
+    let counter_clone = counter.clone();
+    ok!(connection.collation_needed(move |handle, name| {
+        counter_clone.fetch_add(1, Ordering::SeqCst);
+        if name == "by_length" {
+            ok!(handle.create_collation(name, |first, second| {
+                (first.len(), first).cmp(&(second.len(), second))
+            }));
+        }
+    }));
+
+    let query = "SELECT value FROM english ORDER BY value COLLATE by_length LIMIT 2";
+    assert_eq!(read(&connection, query), vec!["ozotype", "cerotype"]);
+    assert_eq!(read(&connection, query), vec!["ozotype", "cerotype"]);
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+    let query = "SELECT value FROM english ORDER BY value COLLATE missing";
+    assert!(connection.prepare(query).is_err());
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn create_collation() {
+    let connection = setup_english(":memory:");
+    ok!(connection.create_collation("reverse", |first, second| second.cmp(first)));
+
+    let query = "SELECT value FROM english ORDER BY value COLLATE reverse LIMIT 2";
+    assert_eq!(read(&connection, query), vec!["undertype", "plastotype"]);
+    let query = "SELECT 'B' = 'b' COLLATE reverse";
+    assert_eq!(read(&connection, query), vec!["0"]);
+
+    ok!(connection.create_collation("fail", |_, _| panic!("fail")));
+    let query = "SELECT 'a' = 'b' COLLATE fail";
+    assert_eq!(read(&connection, query), vec!["1"]);
+
+    ok!(connection.remove_collation("reverse"));
+    let query = "SELECT value FROM english ORDER BY value COLLATE reverse";
+    assert!(connection.prepare(query).is_err());
+}
+
+#[test]
+fn create_scalar_function() {
+    let mut connection = ok!(sqlite::open(":memory:"));
+    let flags = FunctionFlags::new().set_deterministic().set_innocuous();
+    ok!(
+        connection.create_scalar_function("double", 1, flags, |values| {
+            match values[0] {
+                Value::Integer(value) => Value::Integer(2 * value),
+                Value::Float(value) => Value::Float(2.0 * value),
+                _ => Value::Null,
+            }
+        })
+    );
+
+    let mut statement = ok!(connection.prepare("SELECT double(5), double(0.5), double('a')"));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<i64, _>(0)), 10);
+    assert_eq!(ok!(statement.read::<f64, _>(1)), 1.0);
+    assert_eq!(ok!(statement.read::<Value, _>(2)), Value::Null);
+}
+
+#[test]
+fn create_scalar_function_panic() {
+    let mut connection = ok!(sqlite::open(":memory:"));
+    ok!(connection.create_scalar_function("fail", 0, FunctionFlags::new(), |_| panic!("fail")));
+
+    let error = connection.execute("SELECT fail()").unwrap_err();
+    assert_eq!(error.message.as_deref(), Some("the function panicked"));
+    ok!(connection.execute("SELECT 1"));
+}
+
+#[test]
+fn create_scalar_function_variadic() {
+    let mut connection = ok!(sqlite::open(":memory:"));
+    ok!(
+        connection.create_scalar_function("describe", -1, FunctionFlags::new(), |values| {
+            let kinds = values
+                .iter()
+                .map(|value| format!("{:?}", value.kind()))
+                .collect::<Vec<_>>();
+            Value::String(kinds.join(","))
+        })
+    );
+
+    let query = "SELECT describe(), describe(1, 2.0, 'three', X'04', NULL)";
+    let mut statement = ok!(connection.prepare(query));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<String, _>(0)), "");
+    assert_eq!(
+        ok!(statement.read::<String, _>(1)),
+        "Integer,Float,String,Binary,Null"
+    );
+}
+
+#[cfg(feature = "pointer")]
+#[test]
+fn create_scalar_function_with_context() {
+    use std::sync::Arc;
+
+    let mut connection = ok!(sqlite::open(":memory:"));
+    ok!(connection.create_scalar_function_with_context(
+        "describe",
+        2,
+        FunctionFlags::new(),
+        |context| {
+            let name = match context.values()[1] {
+                Value::String(ref name) => name.clone(),
+                _ => return Value::Null,
+            };
+            match context.get_pointer::<Arc<Vec<i64>>>(0, &name) {
+                Some(vector) => Value::Integer(vector.iter().sum()),
+                _ => Value::Null,
+            }
+        }
+    ));
+
+    let vector = Arc::new(vec![1i64, 2, 3]);
+    let mut statement = ok!(connection.prepare("SELECT describe(?1, ?2), ?1"));
+    ok!(statement.bind_pointer(1, Box::new(vector.clone()), "vector"));
+    assert_eq!(Arc::strong_count(&vector), 2);
+    for (name, expected) in [("vector", Value::Integer(6)), ("other", Value::Null)] {
+        ok!(statement.reset());
+        ok!(statement.bind((2, name)));
+        assert_eq!(ok!(statement.next()), State::Row);
+        assert_eq!(ok!(statement.read::<Value, _>(0)), expected);
+        assert_eq!(ok!(statement.read::<Value, _>(1)), Value::Null);
+    }
+
+    ok!(statement.reset());
+    ok!(statement.bind_pointer(1, Box::new(42i64), "vector"));
+    assert_eq!(Arc::strong_count(&vector), 1);
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<Value, _>(0)), Value::Null);
+
+    ok!(statement.reset());
+    ok!(statement.bind_pointer(1, Box::new(vector.clone()), "vector"));
+    drop(statement);
+    assert_eq!(Arc::strong_count(&vector), 1);
+}
+
+#[test]
+fn remove_function() {
+    let mut connection = ok!(sqlite::open(":memory:"));
+    ok!(connection
+        .create_scalar_function("one", 0, FunctionFlags::new(), |_| { Value::Integer(1) }));
+    ok!(connection.execute("SELECT one()"));
+    ok!(connection.remove_function("one", 0));
+    assert!(connection.execute("SELECT one()").is_err());
+}
+
+#[test]
+fn create_aggregate_function() {
+    #[derive(Default)]
+    struct Sum(i64);
+
+    impl Aggregate for Sum {
+        fn step(&mut self, values: &[Value]) {
+            if let Value::Integer(value) = values[0] {
+                self.0 += value;
+            }
+        }
+
+        fn finalize(self) -> Value {
+            Value::Integer(self.0)
+        }
+    }
+
+    #[derive(Default)]
+    struct Concat(Vec<String>);
+
+    impl Aggregate for Concat {
+        fn step(&mut self, values: &[Value]) {
+            let separator = ok!(values[1].try_into::<&str>()).to_string();
+            if !self.0.is_empty() {
+                self.0.push(separator);
+            }
+            self.0.push(ok!(values[0].try_into::<&str>()).to_string());
+        }
+
+        fn finalize(self) -> Value {
+            Value::String(self.0.concat())
+        }
+    }
+
+    let mut connection = setup_english(":memory:");
+    ok!(connection.execute("CREATE TABLE numbers (value INTEGER)"));
+    ok!(connection.execute("INSERT INTO numbers VALUES (1), (2), (3), (NULL)"));
+    ok!(connection.create_aggregate_function::<Sum>("custom_sum", 1));
+    ok!(connection.create_aggregate_function::<Concat>("custom_concat", 2));
+
+    let mut statement = ok!(connection.prepare("SELECT custom_sum(value) FROM numbers"));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<i64, _>(0)), 6);
+
+    let query = "SELECT custom_sum(value) FROM numbers WHERE value > 42";
+    let mut statement = ok!(connection.prepare(query));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<i64, _>(0)), 0);
+
+    let query = "
+        SELECT substr(value, 1, 1), custom_concat(value, '+') FROM english
+        GROUP BY 1 ORDER BY 1
+    ";
+    let mut statement = ok!(connection.prepare(query));
+    let mut groups = vec![];
+    while let State::Row = ok!(statement.next()) {
+        groups.push((
+            ok!(statement.read::<String, _>(0)),
+            ok!(statement.read::<String, _>(1)),
+        ));
+    }
+    assert_eq!(groups.len(), 6);
+    assert_eq!(groups[0], ("c".into(), "cerotype".into()));
+    assert_eq!(groups[4], ("p".into(), "phenotype+plastotype".into()));
+
+    let query = "SELECT custom_concat(value, 42) FROM english";
+    let error = connection.execute(query).unwrap_err();
+    assert_eq!(error.message.as_deref(), Some("the function panicked"));
+}
+
+#[cfg(feature = "window")]
+#[test]
+fn create_window_function() {
+    use sqlite::WindowAggregate;
+
+    #[derive(Default)]
+    struct Sum(i64);
+
+    impl Aggregate for Sum {
+        fn step(&mut self, values: &[Value]) {
+            self.0 += values[0].try_into::<i64>().unwrap_or(0);
+        }
+
+        fn finalize(self) -> Value {
+            Value::Integer(self.0)
+        }
+    }
+
+    impl WindowAggregate for Sum {
+        fn inverse(&mut self, values: &[Value]) {
+            self.0 -= values[0].try_into::<i64>().unwrap_or(0);
+        }
+
+        fn value(&self) -> Value {
+            Value::Integer(self.0)
+        }
+    }
+
+    let mut connection = ok!(sqlite::open(":memory:"));
+    ok!(connection.execute("CREATE TABLE numbers (value INTEGER)"));
+    ok!(connection.execute("INSERT INTO numbers VALUES (1), (2), (3), (NULL), (5)"));
+    ok!(connection.create_window_function::<Sum>("custom_sum", 1));
+
+    let query = "
+        SELECT custom_sum(value) OVER (ORDER BY rowid ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)
+        FROM numbers
+    ";
+    let mut statement = ok!(connection.prepare(query));
+    let mut sums = vec![];
+    while let State::Row = ok!(statement.next()) {
+        sums.push(ok!(statement.read::<i64, _>(0)));
+    }
+    assert_eq!(sums, vec![1, 3, 5, 3, 5]);
+
+    let query = "SELECT custom_sum(value) FROM numbers";
+    let mut statement = ok!(connection.prepare(query));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<i64, _>(0)), 11);
+
+    let query = "SELECT custom_sum(value) OVER () FROM numbers WHERE value > 42";
+    let mut statement = ok!(connection.prepare(query));
+    assert_eq!(ok!(statement.next()), State::Done);
+}
@@ -1 +1,99 @@
+extern crate sqlite;
 
+#[test]
+fn compile_option() {
+    let options = (0..)
+        .map(sqlite::compile_option)
+        .take_while(Option::is_some)
+        .map(Option::unwrap)
+        .collect::<Vec<_>>();
+    assert!(!options.is_empty());
+    assert!(options.iter().all(|option| !option.starts_with("SQLITE_")));
+    assert_eq!(sqlite::compile_option(options.len()), None);
+}
+
+#[test]
+fn compile_option_used() {
+    let option = sqlite::compile_option(0).unwrap();
+    let name = option.split('=').next().unwrap();
+    assert!(sqlite::compile_option_used(name));
+    assert!(sqlite::compile_option_used(&format!("SQLITE_{}", name)));
+    assert!(!sqlite::compile_option_used("MISSING_OPTION"));
+    assert!(!sqlite::compile_option_used("NUL\0"));
+}
+
+#[test]
+fn default_vfs() {
+    let name = sqlite::get_default_vfs().unwrap();
+    sqlite::set_default_vfs(&name).unwrap();
+    assert_eq!(sqlite::get_default_vfs(), Some(name));
+    assert!(sqlite::set_default_vfs("unknown").is_err());
+}
+
+#[test]
+fn error_display() {
+    let connection = sqlite::open(":memory:").unwrap();
+    let error = connection.execute(":)").unwrap_err();
+    assert_eq!(error.code_name(), Some("SQLITE_ERROR"));
+    assert_eq!(error.extended_code(), None);
+    assert_eq!(
+        error.to_string(),
+        r#"unrecognized token: ":" (code 1, SQLITE_ERROR)"#,
+    );
+
+    let error: Box<dyn std::error::Error> = Box::new(error);
+    assert!(error.to_string().starts_with("unrecognized token"));
+}
+
+#[test]
+fn error_from_code() {
+    let error = sqlite::Error::from_code(5);
+    assert_eq!(error.code, Some(5));
+    assert_eq!(error.extended_code(), None);
+    assert_eq!(error.message.as_deref(), Some("database is locked"));
+    assert_eq!(
+        error.to_string(),
+        "database is locked (code 5, SQLITE_BUSY)"
+    );
+
+    let error = sqlite::Error::from_code(2067);
+    assert_eq!(error.code, Some(19));
+    assert_eq!(error.extended_code(), Some(2067));
+    assert_eq!(error.code_name(), Some("SQLITE_CONSTRAINT"));
+}
+
+#[test]
+fn set_log_handler() {
+    use std::sync::{Arc, Mutex};
+
+    if sqlite::version() < 3042000 {
+        assert!(sqlite::set_log_handler(|_, _| {}).is_err());
+        assert!(sqlite::remove_log_handler().is_err());
+        return;
+    }
+
+    let messages = Arc::new(Mutex::new(vec![]));
+    {
+        let messages = messages.clone();
+        sqlite::set_log_handler(move |code, message| {
+            messages.lock().unwrap().push((code, message.to_owned()));
+        })
+        .unwrap();
+    }
+    let connection = sqlite::open(":memory:").unwrap();
+    assert!(connection
+        .execute("SELECT * FROM unknown_log_table")
+        .is_err());
+    sqlite::remove_log_handler().unwrap();
+    assert!(connection
+        .execute("SELECT * FROM unknown_log_table")
+        .is_err());
+
+    let messages = messages.lock().unwrap();
+    let messages = messages
+        .iter()
+        .filter(|(_, message)| message.contains("unknown_log_table"))
+        .collect::<Vec<_>>();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].0, 1);
+}
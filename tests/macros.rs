@@ -0,0 +1,64 @@
+#![cfg(feature = "macros")]
+
+extern crate sqlite;
+
+use sqlite::FromRow;
+
+mod common;
+
+use common::setup_users;
+
+macro_rules! ok(($result:expr) => ($result.unwrap()));
+
+#[derive(Debug, FromRow, PartialEq)]
+struct User {
+    id: i64,
+    #[column = "name"]
+    first_name: String,
+    age: f64,
+    photo: Option<Vec<u8>>,
+    email: Option<String>,
+}
+
+#[test]
+fn from_row() {
+    let connection = setup_users(":memory:");
+    ok!(connection.execute("INSERT INTO users VALUES (2, 'Bob', 69.42, NULL, 'bob@example.com')"));
+    let mut statement = ok!(connection.prepare("SELECT * FROM users ORDER BY id"));
+    let users = ok!(statement
+        .iter()
+        .map(|row| row.and_then(|row| User::from_row(&row)))
+        .collect::<sqlite::Result<Vec<_>>>());
+    assert_eq!(
+        users,
+        vec![
+            User {
+                id: 1,
+                first_name: "Alice".into(),
+                age: 42.69,
+                photo: Some(vec![0x42, 0x69]),
+                email: None,
+            },
+            User {
+                id: 2,
+                first_name: "Bob".into(),
+                age: 69.42,
+                photo: None,
+                email: Some("bob@example.com".into()),
+            },
+        ]
+    );
+
+    let mut statement =
+        ok!(connection.prepare("SELECT id, name, name AS age, photo, email FROM users"));
+    let row = ok!(ok!(statement.iter().next()));
+    assert!(User::from_row(&row).is_err());
+
+    let mut statement = ok!(connection.prepare("SELECT id, name, age, photo FROM users"));
+    let row = ok!(ok!(statement.iter().next()));
+    let error = User::from_row(&row).unwrap_err();
+    assert_eq!(
+        error.message.as_deref(),
+        Some("the row should have a column named email"),
+    );
+}
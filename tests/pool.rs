@@ -0,0 +1,98 @@
+extern crate sqlite;
+extern crate temporary;
+
+use sqlite::ConnectionPool;
+use temporary::Directory;
+
+mod common;
+
+use common::setup_users;
+
+macro_rules! ok(($result:expr) => ($result.unwrap()));
+
+#[test]
+fn build() {
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    let pool = ok!(ConnectionPool::builder(&path).build());
+    assert_eq!(pool.size(), 1);
+    assert_eq!(pool.idle_count(), 1);
+    assert!(path.exists());
+
+    assert!(ConnectionPool::builder(&path).max_size(0).build().is_err());
+    let path = directory.path().join("missing").join("database.sqlite3");
+    assert!(ConnectionPool::builder(&path).build().is_err());
+}
+
+#[test]
+fn get() {
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    setup_users(&path);
+    let pool = ok!(ConnectionPool::builder(&path).max_size(2).build());
+
+    let one = ok!(pool.get());
+    let two = ok!(pool.get());
+    assert_eq!(pool.size(), 2);
+    assert_eq!(pool.idle_count(), 0);
+    ok!(one.execute("INSERT INTO users (id) VALUES (2)"));
+    let count = ok!(two.query_scalar::<i64, _>("SELECT COUNT(*) FROM users"));
+    assert_eq!(count, Some(2));
+
+    drop(one);
+    assert_eq!(pool.idle_count(), 1);
+    let three = ok!(pool.get());
+    assert_eq!(pool.size(), 2);
+    drop((two, three));
+    assert_eq!(pool.idle_count(), 2);
+}
+
+#[test]
+fn get_with_transaction() {
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    setup_users(&path);
+    let pool = ok!(ConnectionPool::builder(&path).build());
+
+    let connection = ok!(pool.get());
+    ok!(connection.execute("BEGIN"));
+    ok!(connection.execute("INSERT INTO users (id) VALUES (2)"));
+    drop(connection);
+    assert_eq!(pool.size(), 0);
+
+    let connection = ok!(pool.get());
+    let count = ok!(connection.query_scalar::<i64, _>("SELECT COUNT(*) FROM users"));
+    assert_eq!(count, Some(1));
+}
+
+#[test]
+fn get_with_threads() {
+    use std::thread;
+
+    let directory = ok!(Directory::new("sqlite"));
+    let path = directory.path().join("database.sqlite3");
+    setup_users(&path);
+    let pool = ok!(ConnectionPool::builder(&path)
+        .max_size(2)
+        .busy_timeout(5000)
+        .build());
+
+    let threads = (0..8)
+        .map(|index| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                let connection = ok!(pool.get());
+                let query = format!("INSERT INTO users (id) VALUES ({})", index + 2);
+                ok!(connection.execute(query));
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        ok!(thread.join());
+    }
+
+    assert!(pool.size() <= 2);
+    let connection = ok!(pool.get());
+    let count = ok!(connection.query_scalar::<i64, _>("SELECT COUNT(*) FROM users"));
+    assert_eq!(count, Some(9));
+}
@@ -1,6 +1,6 @@
 extern crate sqlite;
 
-use sqlite::{Connection, State, Statement, Type, Value};
+use sqlite::{Connection, State, Statement, ToSql, Type, Value};
 
 mod common;
 
@@ -67,6 +67,43 @@ fn bind_with_index() {
     assert_eq!(ok!(statement.next()), State::Done);
 }
 
+#[test]
+fn bind_with_array() {
+    let connection = setup_users(":memory:");
+    let query = "INSERT INTO users VALUES (?, ?, ?, ?, ?)";
+    let mut statement = ok!(connection.prepare(query));
+
+    ok!(statement.bind([
+        Value::Integer(2),
+        Value::String("Bob".into()),
+        Value::Float(69.42),
+        Value::Binary([0x69u8, 0x42u8].to_vec()),
+        Value::Null,
+    ]));
+    assert_eq!(ok!(statement.next()), State::Done);
+
+    ok!(statement.reset());
+    ok!(statement.bind([(1, 3i64), (2, 42i64)]));
+    ok!(statement.bind([(3, "Eve")]));
+    assert_eq!(ok!(statement.next()), State::Done);
+
+    let query = "SELECT name FROM users WHERE id = :id";
+    let mut statement = ok!(connection.prepare(query));
+    ok!(statement.bind([(":id", 2)]));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<String, _>(0)), "Bob");
+}
+
+#[test]
+fn bind_with_nothing() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT name FROM users";
+    let mut statement = ok!(connection.prepare(query));
+
+    ok!(statement.bind(()));
+    assert_eq!(ok!(statement.next()), State::Row);
+}
+
 #[test]
 fn bind_with_name() {
     let connection = setup_users(":memory:");
@@ -97,6 +134,113 @@ fn bind_with_name() {
     assert_eq!(ok!(statement.next()), State::Done);
 }
 
+#[test]
+fn bind_to_sql() {
+    let connection = setup_users(":memory:");
+    let query = "INSERT INTO users VALUES (?, ?, ?, ?, ?)";
+    let mut statement = ok!(connection.prepare(query));
+
+    let photo = vec![0x69u8, 0x42u8];
+    let email: Option<String> = None;
+    let values: [&dyn ToSql; 5] = [&2i32, &"Bob", &69.42f32, &photo, &email];
+    for (index, value) in values.iter().enumerate() {
+        ok!(statement.bind_to_sql(index + 1, *value));
+    }
+    assert_eq!(ok!(statement.next()), State::Done);
+
+    ok!(statement.reset());
+    ok!(statement.bind(&values[..]));
+    assert_eq!(ok!(statement.next()), State::Done);
+
+    let query = "SELECT name, photo, email FROM users WHERE id = ?";
+    let mut statement = ok!(connection.prepare(query));
+    ok!(statement.bind_to_sql(1, &2i64));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<String, _>(0)), "Bob");
+    assert_eq!(ok!(statement.read::<Vec<u8>, _>(1)), photo);
+    assert_eq!(ok!(statement.read::<Option<String>, _>(2)), None);
+}
+
+#[test]
+fn bind_values() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT COUNT(*) FROM users WHERE id = ? AND name = :name AND age > ?";
+    let mut statement = ok!(connection.prepare(query));
+
+    let values = vec![
+        Value::Integer(1),
+        Value::String("Alice".into()),
+        Value::Float(40.0),
+    ];
+    ok!(statement.bind_values(&values));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<i64, _>(0)), 1);
+
+    ok!(statement.reset());
+    ok!(statement.bind_value(":name", &Value::String("Bob".into())));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<i64, _>(0)), 0);
+
+    ok!(statement.reset());
+    assert!(statement.bind_value(0, &Value::Null).is_err());
+    assert!(statement.bind_values(&vec![Value::Null; 4]).is_err());
+}
+
+#[test]
+fn bind_zeroblob() {
+    let connection = ok!(sqlite::open(":memory:"));
+    ok!(connection.execute("CREATE TABLE files (name TEXT, data BLOB)"));
+    let mut statement = ok!(connection.prepare("INSERT INTO files VALUES (?, :data)"));
+    ok!(statement.bind((1, "empty")));
+    ok!(statement.bind_zeroblob(":data", 0));
+    assert_eq!(ok!(statement.next()), State::Done);
+    ok!(statement.reset());
+    ok!(statement.bind((1, "full")));
+    ok!(statement.bind_zeroblob(2, 4));
+    assert_eq!(ok!(statement.next()), State::Done);
+    assert!(statement.bind_zeroblob(3, 4).is_err());
+
+    let mut statement = ok!(connection.prepare("SELECT data FROM files ORDER BY name"));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<Vec<u8>, _>(0)), Vec::<u8>::new());
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<Vec<u8>, _>(0)), vec![0; 4]);
+}
+
+#[test]
+fn clear_bindings() {
+    let connection = setup_users(":memory:");
+    let query = "INSERT INTO users VALUES (?, ?, NULL, NULL, NULL)";
+    let mut statement = ok!(connection.prepare(query));
+    ok!(statement.bind((1, 2)));
+    ok!(statement.bind((2, "Bob")));
+    assert_eq!(ok!(statement.next()), State::Done);
+    ok!(statement.reset());
+    ok!(statement.bind((1, 3)));
+    assert_eq!(ok!(statement.next()), State::Done);
+    ok!(statement.reset());
+    ok!(statement.clear_bindings());
+    assert_eq!(ok!(statement.next()), State::Done);
+
+    let query = "SELECT id, name FROM users WHERE id > 1 ORDER BY rowid";
+    let mut statement = ok!(connection.prepare(query));
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(
+        ok!(statement.read::<Option<String>, _>(1)),
+        Some("Bob".into())
+    );
+    assert_eq!(ok!(statement.next()), State::Row);
+    assert_eq!(ok!(statement.read::<i64, _>(0)), 3);
+    assert_eq!(
+        ok!(statement.read::<Option<String>, _>(1)),
+        Some("Bob".into())
+    );
+    assert_eq!(ok!(statement.next()), State::Done);
+
+    let query = "SELECT COUNT(*) FROM users WHERE id IS NULL AND name IS NULL";
+    assert_eq!(ok!(connection.query_scalar::<i64, _>(query)), Some(1));
+}
+
 #[test]
 fn column_count() {
     let connection = setup_users(":memory:");
@@ -107,6 +251,35 @@ fn column_count() {
     assert_eq!(statement.column_count(), 5);
 }
 
+#[test]
+fn column_database_name() {
+    let connection = setup_users(":memory:");
+    ok!(connection.execute("ATTACH DATABASE ':memory:' AS other"));
+    ok!(connection.execute("CREATE TABLE other.items (name TEXT)"));
+    let query = "
+        SELECT users.name, items.name, COUNT(*)
+        FROM users CROSS JOIN other.items
+    ";
+    let statement = ok!(connection.prepare(query));
+
+    assert_eq!(ok!(statement.column_database_name(0)), Some("main"));
+    assert_eq!(ok!(statement.column_database_name(1)), Some("other"));
+    assert_eq!(ok!(statement.column_database_name(2)), None);
+}
+
+#[test]
+fn column_decltype() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT id, name AS user_name, age + 1, photo FROM users";
+    let statement = ok!(connection.prepare(query));
+
+    assert_eq!(ok!(statement.column_decltype(0)), Some("INTEGER"));
+    assert_eq!(ok!(statement.column_decltype("user_name")), Some("TEXT"));
+    assert_eq!(ok!(statement.column_decltype(2)), None);
+    assert_eq!(ok!(statement.column_decltype(3)), Some("BLOB"));
+    assert!(statement.column_decltype(4).is_err());
+}
+
 #[test]
 fn column_name() {
     let connection = setup_users(":memory:");
@@ -118,6 +291,34 @@ fn column_name() {
     assert_eq!("user_photo", ok!(statement.column_name(3)));
 }
 
+#[test]
+fn column_origin_name() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT id, name AS user_name, age + 1, COUNT(*) FROM users";
+    let statement = ok!(connection.prepare(query));
+
+    assert_eq!(ok!(statement.column_origin_name(0)), Some("id"));
+    assert_eq!(ok!(statement.column_origin_name("user_name")), Some("name"));
+    assert_eq!(ok!(statement.column_origin_name(2)), None);
+    assert_eq!(ok!(statement.column_origin_name(3)), None);
+}
+
+#[test]
+fn column_table_name() {
+    let connection = setup_users(":memory:");
+    ok!(connection.execute("CREATE TABLE items (id INTEGER, user_id INTEGER)"));
+    let query = "
+        SELECT users.name, items.id AS item_id, 1 + 1, MAX(items.id)
+        FROM users JOIN items ON items.user_id = users.id
+    ";
+    let statement = ok!(connection.prepare(query));
+
+    assert_eq!(ok!(statement.column_table_name(0)), Some("users"));
+    assert_eq!(ok!(statement.column_table_name("item_id")), Some("items"));
+    assert_eq!(ok!(statement.column_table_name(2)), None);
+    assert_eq!(ok!(statement.column_table_name(3)), None);
+}
+
 #[test]
 fn column_type() {
     let connection = setup_users(":memory:");
@@ -159,6 +360,62 @@ fn count() {
     assert_eq!(count, 6);
 }
 
+#[test]
+fn expanded_sql() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT * FROM users WHERE id = ? AND name = ? AND age > ? AND photo = ?";
+    let mut statement = ok!(connection.prepare(query));
+
+    assert_eq!(
+        ok!(statement.expanded_sql().ok_or(())),
+        "SELECT * FROM users WHERE id = NULL AND name = NULL AND age > NULL AND photo = NULL"
+    );
+
+    ok!(statement.bind::<&[Value]>(
+        &[
+            Value::Integer(1),
+            Value::String("Alice".into()),
+            Value::Float(40.5),
+            Value::Binary(vec![0x42, 0x69]),
+        ][..]
+    ));
+    let sql = ok!(statement.expanded_sql().ok_or(()));
+    assert!(!sql.contains('?'));
+    assert_eq!(
+        sql,
+        "SELECT * FROM users WHERE id = 1 AND name = 'Alice' AND age > 40.5 AND photo = x'4269'"
+    );
+}
+
+#[cfg(feature = "isexplain")]
+#[test]
+fn is_explain() {
+    use sqlite::ExplainMode;
+
+    let connection = setup_users(":memory:");
+    let explain = |query| ok!(connection.prepare(query)).is_explain();
+
+    assert_eq!(explain("SELECT * FROM users"), ExplainMode::None);
+    assert_eq!(explain("EXPLAIN SELECT * FROM users"), ExplainMode::Explain);
+    assert_eq!(
+        explain("EXPLAIN QUERY PLAN SELECT * FROM users"),
+        ExplainMode::ExplainQueryPlan
+    );
+}
+
+#[test]
+fn is_readonly() {
+    let connection = setup_users(":memory:");
+    let readonly = |query| ok!(connection.prepare(query)).is_readonly();
+
+    assert!(readonly("SELECT * FROM users"));
+    assert!(readonly("BEGIN"));
+    assert!(!readonly("INSERT INTO users (id) VALUES (2)"));
+    assert!(!readonly("UPDATE users SET age = 0"));
+    assert!(!readonly("DELETE FROM users"));
+    assert!(!readonly("CREATE TABLE items (id INTEGER)"));
+}
+
 #[test]
 fn parameter_index() {
     let connection = setup_users(":memory:");
@@ -173,6 +430,21 @@ fn parameter_index() {
     assert_eq!(ok!(statement.next()), State::Done);
 }
 
+#[test]
+fn parameter_name() {
+    let connection = setup_users(":memory:");
+    let query = "SELECT * FROM users WHERE id = ? AND name = :name AND age > @age OR id = ?5";
+    let statement = ok!(connection.prepare(query));
+    assert_eq!(statement.parameter_count(), 5);
+    assert_eq!(statement.parameter_name(0), None);
+    assert_eq!(statement.parameter_name(1), None);
+    assert_eq!(statement.parameter_name(2), Some(":name"));
+    assert_eq!(statement.parameter_name(3), Some("@age"));
+    assert_eq!(statement.parameter_name(4), None);
+    assert_eq!(statement.parameter_name(5), Some("?5"));
+    assert_eq!(statement.parameter_name(6), None);
+}
+
 #[test]
 fn read_with_index() {
     let connection = setup_users(":memory:");
@@ -181,6 +453,8 @@ fn read_with_index() {
 
     assert_eq!(ok!(statement.next()), State::Row);
     assert_eq!(ok!(statement.read::<i64, _>(0)), 1);
+    assert_eq!(ok!(statement.read::<i64, _>(0i32)), 1);
+    assert!(statement.read::<i64, _>(-1i32).is_err());
     assert_eq!(ok!(statement.read::<String, _>(1)), String::from("Alice"));
     assert_eq!(ok!(statement.read::<f64, _>(2)), 42.69);
     assert_eq!(ok!(statement.read::<Vec<u8>, _>(3)), vec![0x42, 0x69]);
@@ -242,12 +516,37 @@ fn read_with_name() {
         ok!(statement.read::<String, _>("name")),
         String::from("Alice")
     );
+    let name = String::from("name");
+    assert_eq!(ok!(statement.read::<String, _>(&name)), "Alice");
     assert_eq!(ok!(statement.read::<f64, _>("age")), 42.69);
     assert_eq!(ok!(statement.read::<Vec<u8>, _>("photo")), vec![0x42, 0x69]);
     assert_eq!(ok!(statement.read::<Value, _>("email")), Value::Null);
     assert_eq!(ok!(statement.next()), State::Done);
 }
 
+#[test]
+fn status() {
+    use sqlite::StmtStatus;
+
+    let connection = setup_english(":memory:");
+    let query = "SELECT value FROM english WHERE value LIKE '%type' ORDER BY value";
+    let mut statement = ok!(connection.prepare(query));
+    while let State::Row = ok!(statement.next()) {}
+    assert!(statement.status(StmtStatus::FullscanStep, false) > 0);
+    assert_eq!(statement.status(StmtStatus::Sort, false), 1);
+    assert!(statement.status(StmtStatus::VmStep, true) > 0);
+    assert_eq!(statement.status(StmtStatus::VmStep, false), 0);
+    assert!(statement.status(StmtStatus::Memused, false) > 0);
+    ok!(statement.reset());
+    assert_eq!(statement.status(StmtStatus::Run, false), 1);
+
+    ok!(connection.execute("CREATE INDEX english_value ON english (value)"));
+    let query = "SELECT value FROM english WHERE value = 'ozotype'";
+    let mut statement = ok!(connection.prepare(query));
+    while let State::Row = ok!(statement.next()) {}
+    assert_eq!(statement.status(StmtStatus::FullscanStep, false), 0);
+}
+
 #[test]
 fn workflow_1() {
     struct Database<'l> {
@@ -271,7 +570,7 @@ fn workflow_1() {
 
     let mut database = Database {
         connection: &connection,
-        statement: statement,
+        statement,
     };
 
     for _ in 0..5 {
@@ -0,0 +1,79 @@
+extern crate sqlite;
+
+use sqlite::State;
+
+mod common;
+
+use common::setup_users;
+
+macro_rules! ok(($result:expr) => ($result.unwrap()));
+
+fn count(connection: &sqlite::Connection) -> i64 {
+    let mut statement = ok!(connection.prepare("SELECT COUNT(*) FROM users"));
+    assert_eq!(ok!(statement.next()), State::Row);
+    ok!(statement.read::<i64, _>(0))
+}
+
+#[test]
+fn commit() {
+    let mut connection = setup_users(":memory:");
+
+    let transaction = ok!(connection.transaction());
+    ok!(transaction.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+    assert_eq!(count(&transaction), 2);
+    ok!(transaction.commit());
+
+    assert_eq!(count(&connection), 2);
+}
+
+#[test]
+fn drop() {
+    let mut connection = setup_users(":memory:");
+
+    {
+        let transaction = ok!(connection.transaction());
+        ok!(transaction.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+        assert_eq!(count(&transaction), 2);
+    }
+
+    assert_eq!(count(&connection), 1);
+}
+
+#[test]
+fn rollback() {
+    let mut connection = setup_users(":memory:");
+
+    let transaction = ok!(connection.transaction());
+    ok!(transaction.execute("DELETE FROM users"));
+    assert_eq!(count(&transaction), 0);
+    ok!(transaction.rollback());
+
+    assert_eq!(count(&connection), 1);
+}
+
+#[test]
+fn savepoint() {
+    let mut connection = setup_users(":memory:");
+
+    let mut transaction = ok!(connection.transaction());
+    {
+        let savepoint = ok!(transaction.savepoint("first"));
+        ok!(savepoint.execute("INSERT INTO users VALUES (2, 'Bob', NULL, NULL, NULL)"));
+        ok!(savepoint.release());
+    }
+    {
+        let mut savepoint = ok!(transaction.savepoint("second"));
+        ok!(savepoint.execute("INSERT INTO users VALUES (3, 'Eve', NULL, NULL, NULL)"));
+        {
+            let savepoint = ok!(savepoint.savepoint("third"));
+            ok!(savepoint.execute("DELETE FROM users"));
+            assert_eq!(count(&savepoint), 0);
+        }
+        assert_eq!(count(&savepoint), 3);
+        ok!(savepoint.rollback());
+    }
+    assert_eq!(count(&transaction), 2);
+    ok!(transaction.commit());
+
+    assert_eq!(count(&connection), 2);
+}
@@ -0,0 +1,315 @@
+extern crate sqlite;
+
+use sqlite::{Blob, SqlEqual, ToSql, Value};
+
+mod common;
+
+use common::setup_users;
+
+macro_rules! ok(($result:expr) => ($result.unwrap()));
+
+#[test]
+fn coerce_to_float() {
+    assert_eq!(Value::Float(0.5).coerce_to_float(), Some(0.5));
+    assert_eq!(Value::Integer(-42).coerce_to_float(), Some(-42.0));
+    assert_eq!(
+        Value::String(" 1.5e2 ".into()).coerce_to_float(),
+        Some(150.0)
+    );
+    assert_eq!(Value::String("42".into()).coerce_to_float(), Some(42.0));
+    assert_eq!(Value::String("inf".into()).coerce_to_float(), None);
+    assert_eq!(Value::String("4 2".into()).coerce_to_float(), None);
+    assert_eq!(Value::String("".into()).coerce_to_float(), None);
+    assert_eq!(Value::Binary(vec![0x31]).coerce_to_float(), None);
+    assert_eq!(Value::Null.coerce_to_float(), None);
+}
+
+#[test]
+fn coerce_to_integer() {
+    assert_eq!(Value::Integer(42).coerce_to_integer(), Some(42));
+    assert_eq!(Value::Float(-2.9).coerce_to_integer(), Some(-2));
+    assert_eq!(Value::Float(1e300).coerce_to_integer(), Some(i64::MAX));
+    assert_eq!(Value::Float(f64::NAN).coerce_to_integer(), None);
+    assert_eq!(Value::String("+42".into()).coerce_to_integer(), Some(42));
+    assert_eq!(Value::String(" -7 ".into()).coerce_to_integer(), Some(-7));
+    assert_eq!(Value::String("3.99".into()).coerce_to_integer(), Some(3));
+    assert_eq!(Value::String("1e3".into()).coerce_to_integer(), Some(1000));
+    assert_eq!(Value::String("Alice".into()).coerce_to_integer(), None);
+    assert_eq!(Value::String("e".into()).coerce_to_integer(), None);
+    assert_eq!(Value::Null.coerce_to_integer(), None);
+
+    let connection = setup_users(":memory:");
+    let query = "SELECT CAST('3.99' AS INTEGER), CAST(-2.9 AS INTEGER)";
+    let mut statement = ok!(connection.prepare(query));
+    assert_eq!(ok!(statement.next()), sqlite::State::Row);
+    for (index, value) in ["3.99", "-2.9"].iter().enumerate() {
+        let expected = ok!(statement.read::<i64, _>(index));
+        let value = Value::String(value.to_string());
+        assert_eq!(value.coerce_to_integer(), Some(expected));
+    }
+}
+
+#[test]
+fn display() {
+    assert_eq!(Value::Binary(vec![0x42, 0x0A]).to_string(), "X'420A'");
+    assert_eq!(Value::Binary(vec![]).to_string(), "X''");
+    assert_eq!(Value::Float(42.0).to_string(), "42.0");
+    assert_eq!(Value::Float(-0.5).to_string(), "-0.5");
+    assert_eq!(Value::Integer(-42).to_string(), "-42");
+    assert_eq!(Value::String("Alice's".into()).to_string(), "Alice's");
+    assert_eq!(Value::Null.to_string(), "NULL");
+    assert_eq!(format!("{:>5}", Value::Integer(42)), "   42");
+}
+
+#[test]
+fn eq_and_hash() {
+    use std::collections::HashSet;
+
+    assert_eq!(Value::Null, Value::Null);
+    assert_eq!(Value::Float(0.0), Value::Float(-0.0));
+    assert_eq!(Value::Float(f64::NAN), Value::Float(f64::NAN));
+    assert_ne!(Value::Integer(1), Value::Float(1.0));
+    assert_ne!(Value::String("1".into()), Value::Integer(1));
+
+    let values = vec![
+        Value::Null,
+        Value::Null,
+        Value::Integer(1),
+        Value::Float(1.0),
+        Value::Float(0.0),
+        Value::Float(-0.0),
+        Value::String("a".into()),
+        Value::Binary(b"a".to_vec()),
+    ];
+    assert_eq!(values.into_iter().collect::<HashSet<_>>().len(), 6);
+}
+
+#[test]
+fn is_null() {
+    let values = [
+        Value::Binary(vec![0x42]),
+        Value::Float(42.69),
+        Value::Integer(42),
+        Value::String("Alice".into()),
+        Value::Null,
+    ];
+    let flags = values
+        .iter()
+        .map(|value| {
+            [
+                value.is_binary(),
+                value.is_float(),
+                value.is_integer(),
+                value.is_text(),
+                value.is_null(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    for (index, flags) in flags.iter().enumerate() {
+        for (other, &flag) in flags.iter().enumerate() {
+            assert_eq!(flag, index == other);
+        }
+    }
+}
+
+#[test]
+fn ord() {
+    let mut values = vec![
+        Value::Binary(vec![0x01]),
+        Value::String("b".into()),
+        Value::Float(1.5),
+        Value::Integer(i64::MAX),
+        Value::Float(1e19),
+        Value::Integer(2),
+        Value::Float(-1e19),
+        Value::Binary(vec![]),
+        Value::String("a".into()),
+        Value::Float(2.0),
+        Value::Null,
+        Value::Integer(-1),
+        Value::Float(-1.5),
+    ];
+    values.sort();
+    assert_eq!(
+        values,
+        vec![
+            Value::Null,
+            Value::Float(-1e19),
+            Value::Float(-1.5),
+            Value::Integer(-1),
+            Value::Float(1.5),
+            Value::Integer(2),
+            Value::Float(2.0),
+            Value::Integer(i64::MAX),
+            Value::Float(1e19),
+            Value::String("a".into()),
+            Value::String("b".into()),
+            Value::Binary(vec![]),
+            Value::Binary(vec![0x01]),
+        ]
+    );
+    assert!(Value::Integer(-2) < Value::Float(-1.5));
+    assert!(Value::Float(-2.5) < Value::Integer(-2));
+}
+
+#[test]
+fn sql_equal() {
+    assert!(SqlEqual(Value::Integer(1)) == SqlEqual(Value::Float(1.0)));
+    assert!(SqlEqual(Value::Float(1.0)) == SqlEqual(Value::Integer(1)));
+    assert!(SqlEqual(Value::Integer(1)) != SqlEqual(Value::Float(1.5)));
+    assert!(SqlEqual(Value::String("a".into())) == SqlEqual(Value::String("a".into())));
+    assert!(SqlEqual(Value::String("1".into())) != SqlEqual(Value::Integer(1)));
+    assert!(SqlEqual(Value::Null) != SqlEqual(Value::Null));
+    assert!(SqlEqual(Value::Float(f64::NAN)) != SqlEqual(Value::Float(f64::NAN)));
+}
+
+#[test]
+fn to_sql() {
+    assert_eq!(true.to_sql(), Value::Integer(1));
+    assert_eq!(42i32.to_sql(), Value::Integer(42));
+    assert_eq!(u32::MAX.to_sql(), Value::Integer(u32::MAX as i64));
+    assert_eq!(0.5f32.to_sql(), Value::Float(0.5));
+    assert_eq!("Alice".to_sql(), Value::String("Alice".into()));
+    assert_eq!(String::from("Bob").to_sql(), Value::String("Bob".into()));
+    assert_eq!([0x42u8][..].to_sql(), Value::Binary(vec![0x42]));
+    assert_eq!(Blob(vec![0x42]).to_sql(), Value::Binary(vec![0x42]));
+    assert_eq!(Some(42i64).to_sql(), Value::Integer(42));
+    assert_eq!(None::<&str>.to_sql(), Value::Null);
+    assert_eq!(Value::Float(1.5).to_sql(), Value::Float(1.5));
+}
+
+#[test]
+fn try_into_binary() {
+    let binary = Value::Binary(vec![0x42, 0x69]);
+    let string = Value::String("Bi".into());
+    assert_eq!(ok!(binary.try_into::<Vec<u8>>()), vec![0x42, 0x69]);
+    assert_eq!(ok!(string.try_into::<Vec<u8>>()), vec![0x42, 0x69]);
+    assert_eq!(ok!(binary.try_into::<Blob>()), Blob(vec![0x42, 0x69]));
+    assert!(string.try_into::<Blob>().is_err());
+    assert!(Value::Integer(1).try_into::<Vec<u8>>().is_err());
+    assert_eq!(ok!(Value::Null.try_into::<Option<Blob>>()), None);
+    assert_eq!(ok!(Value::Null.try_into::<Option<Vec<u8>>>()), None);
+    assert_eq!(
+        ok!(string.try_into::<Option<Vec<u8>>>()),
+        Some(vec![0x42, 0x69])
+    );
+    assert_eq!(Value::from(Blob(vec![0x42])), Value::Binary(vec![0x42]));
+
+    let connection = setup_users(":memory:");
+    let query = "SELECT name, photo FROM users";
+    let mut statement = ok!(connection.prepare(query));
+    assert_eq!(ok!(statement.next()), sqlite::State::Row);
+    assert_eq!(ok!(statement.read::<Vec<u8>, _>("name")), b"Alice".to_vec());
+    assert!(statement.read::<Blob, _>("name").is_err());
+    assert_eq!(
+        ok!(statement.read::<Blob, _>("photo")),
+        Blob(vec![0x42, 0x69])
+    );
+
+    ok!(statement.reset());
+    let row = ok!(ok!(statement.iter().next()));
+    assert_eq!(row.read::<Vec<u8>, _>("name"), b"Alice".to_vec());
+    assert!(row.try_read::<Blob, _>("name").is_err());
+    assert_eq!(row.read::<Blob, _>("photo"), Blob(vec![0x42, 0x69]));
+
+    let mut statement = ok!(connection.prepare("SELECT typeof(?)"));
+    ok!(statement.bind((1, Blob(b"Bob".to_vec()))));
+    assert_eq!(ok!(statement.next()), sqlite::State::Row);
+    assert_eq!(ok!(statement.read::<String, _>(0)), "blob");
+}
+
+#[test]
+fn try_into_bool() {
+    assert!(!ok!(Value::Integer(0).try_into::<bool>()));
+    assert!(ok!(Value::Integer(1).try_into::<bool>()));
+    assert!(Value::Integer(2).try_into::<bool>().is_err());
+    assert!(Value::Integer(-1).try_into::<bool>().is_err());
+    assert!(Value::Float(1.0).try_into::<bool>().is_err());
+    assert!(Value::Null.try_into::<bool>().is_err());
+    assert_eq!(ok!(Value::Null.try_into::<Option<bool>>()), None);
+    assert_eq!(
+        ok!(Value::Integer(1).try_into::<Option<bool>>()),
+        Some(true)
+    );
+
+    let connection = setup_users(":memory:");
+    let query = "SELECT id = 1 AS flag, id = 2 AS other, id AS third FROM users";
+    let mut statement = ok!(connection.prepare(query));
+    let row = ok!(ok!(statement.iter().next()));
+    assert!(row.read::<bool, _>("flag"));
+    assert!(!row.read::<bool, _>("other"));
+    assert!(row.try_read::<bool, _>("third").is_ok());
+}
+
+#[test]
+fn try_into_float() {
+    assert_eq!(ok!(Value::Float(0.5).try_into::<f32>()), 0.5);
+    assert_eq!(ok!(Value::Float(1e300).try_into::<f32>()), f32::INFINITY);
+    assert!(Value::Integer(1).try_into::<f32>().is_err());
+    assert!(Value::Null.try_into::<f32>().is_err());
+    assert_eq!(ok!(Value::Null.try_into::<Option<f32>>()), None);
+    assert_eq!(ok!(Value::Float(2.0).try_into::<Option<f32>>()), Some(2.0));
+
+    let connection = setup_users(":memory:");
+    let mut statement = ok!(connection.prepare("SELECT age FROM users"));
+    assert_eq!(ok!(statement.next()), sqlite::State::Row);
+    assert_eq!(ok!(statement.read::<f32, _>("age")), 42.69);
+    assert_eq!(ok!(statement.read::<Option<f32>, _>("age")), Some(42.69));
+
+    ok!(statement.reset());
+    let row = ok!(ok!(statement.iter().next()));
+    assert_eq!(row.read::<f32, _>("age"), 42.69);
+}
+
+#[test]
+fn try_into_integer() {
+    assert_eq!(ok!(Value::Integer(127).try_into::<i8>()), 127);
+    assert!(Value::Integer(128).try_into::<i8>().is_err());
+    assert_eq!(ok!(Value::Integer(-32768).try_into::<i16>()), -32768);
+    assert_eq!(ok!(Value::Integer(-1).try_into::<i32>()), -1);
+    assert!(Value::Integer(i64::from(i32::MAX) + 1)
+        .try_into::<i32>()
+        .is_err());
+    assert_eq!(ok!(Value::Integer(255).try_into::<u8>()), 255);
+    assert!(Value::Integer(-1).try_into::<u8>().is_err());
+    assert!(Value::Integer(65536).try_into::<u16>().is_err());
+    assert_eq!(ok!(Value::Integer(42).try_into::<u32>()), 42);
+    assert_eq!(
+        ok!(Value::Integer(i64::MAX).try_into::<u64>()),
+        i64::MAX as u64
+    );
+    assert!(Value::Integer(-1).try_into::<u64>().is_err());
+    assert!(Value::Float(1.0).try_into::<i32>().is_err());
+    assert_eq!(ok!(Value::Null.try_into::<Option<u16>>()), None);
+    assert_eq!(ok!(Value::Integer(7).try_into::<Option<u16>>()), Some(7));
+
+    let connection = setup_users(":memory:");
+    let query = "SELECT id, -id AS negative, 3000000000 AS large FROM users";
+    let mut statement = ok!(connection.prepare(query));
+    assert_eq!(ok!(statement.next()), sqlite::State::Row);
+    assert_eq!(ok!(statement.read::<i32, _>("id")), 1);
+    assert_eq!(ok!(statement.read::<u8, _>("id")), 1);
+    assert!(statement.read::<u32, _>("negative").is_err());
+    assert_eq!(ok!(statement.read::<u32, _>("large")), 3_000_000_000);
+    assert!(statement.read::<i32, _>("large").is_err());
+    assert_eq!(ok!(statement.read::<Option<i16>, _>("negative")), Some(-1));
+
+    ok!(statement.reset());
+    let row = ok!(ok!(statement.iter().next()));
+    assert_eq!(row.read::<i32, _>("id"), 1);
+    assert!(row.try_read::<u64, _>("negative").is_err());
+}
+
+#[test]
+fn type_name() {
+    let connection = setup_users(":memory:");
+    let query =
+        "SELECT *, typeof(id), typeof(name), typeof(age), typeof(photo), typeof(email) FROM users";
+    let mut statement = ok!(connection.prepare(query));
+    let row = ok!(ok!(statement.iter().next()));
+    let values: Vec<Value> = row.into();
+    for index in 0..5 {
+        let name = ok!(values[index + 5].try_into::<&str>()).to_uppercase();
+        assert_eq!(values[index].type_name(), name);
+    }
+}